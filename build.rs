@@ -3,7 +3,7 @@
 extern crate phf_codegen;
 
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
@@ -13,6 +13,14 @@ fn main() {
 
     write_pinyin_map(&mut out_file);
     write_zhuyin_map(&mut out_file);
+    write_syllable_map(&mut out_file);
+
+    if env::var_os("CARGO_FEATURE_HANZI").is_some() {
+        let path = Path::new(&env::var("OUT_DIR").unwrap()).join("hanzi.rs");
+        write_hanzi_map(&mut BufWriter::new(File::create(path).unwrap()));
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=data/hanzi.txt");
 }
 
 #[rustfmt::skip]
@@ -71,6 +79,7 @@ fn write_zhuyin_map<W: Write>(file: &mut W) {
             .entry("ㄩ", "\"v\"").entry("ㄜ", "\"e\"").entry("ㄠ", "\"ao\"").entry("ㄤ", "\"ang\"")
                                 .entry("ㄝ", "\"e\"").entry("ㄡ", "\"ou\"").entry("ㄥ", "\"eng\"")
 
+            .entry("ㄧㄛ", "\"io\"")
             .entry("ㄧㄚ", "\"ia\"") .entry("ㄨㄚ", "\"ua\"") .entry("ㄧㄥ", "\"ing\"").entry("ㄧㄤ", "\"iang\"")
             .entry("ㄧㄝ", "\"ie\"") .entry("ㄨㄛ", "\"uo\"") .entry("ㄨㄥ", "\"ong\"").entry("ㄨㄤ", "\"uang\"")
             .entry("ㄧㄠ", "\"iao\"").entry("ㄨㄞ", "\"uai\"").entry("ㄩㄝ", "\"ve\"") .entry("ㄩㄥ", "\"iong\"")
@@ -85,3 +94,134 @@ fn write_zhuyin_map<W: Write>(file: &mut W) {
     .unwrap();
     writeln!(file, ";").unwrap();
 }
+
+/// Every syllable of standard Mandarin, spelled as numbered pinyin without the tone
+#[rustfmt::skip]
+static SYLLABLE_LIST: &[&str] = &[
+    "a", "o", "e", "ai", "ei", "ao", "ou", "an", "en", "ang", "eng", "er",
+    // Syllabic nasals, such as 呣 m2, 嗯 n2 and ng2
+    "m", "n", "ng",
+
+    "yi", "ya", "yo", "ye", "yao", "you", "yan", "yin", "yang", "ying", "yong", "yv", "yve", "yvan", "yvn",
+    "wu", "wa", "wo", "wai", "wei", "wan", "wen", "wang", "weng",
+
+    "ba", "bo", "bai", "bei", "bao", "ban", "ben", "bang", "beng",
+    "bi", "bie", "biao", "bian", "bin", "bing", "bu",
+    "pa", "po", "pai", "pei", "pao", "pou", "pan", "pen", "pang", "peng",
+    "pi", "pie", "piao", "pian", "pin", "ping", "pu",
+    "ma", "mo", "me", "mai", "mei", "mao", "mou", "man", "men", "mang", "meng",
+    "mi", "mie", "miao", "miu", "mian", "min", "ming", "mu",
+    "fa", "fo", "fei", "fou", "fan", "fen", "fang", "feng", "fu",
+
+    "da", "de", "dai", "dei", "dao", "dou", "dan", "den", "dang", "deng", "dong",
+    "di", "dia", "die", "diao", "diu", "dian", "ding", "du", "duo", "dui", "duan", "dun",
+    "ta", "te", "tai", "tao", "tou", "tan", "tang", "teng", "tong",
+    "ti", "tie", "tiao", "tian", "ting", "tu", "tuo", "tui", "tuan", "tun",
+    "na", "ne", "nai", "nei", "nao", "nou", "nan", "nen", "nang", "neng", "nong",
+    "ni", "nie", "niao", "niu", "nian", "nin", "niang", "ning", "nu", "nuo", "nuan", "nv", "nve",
+    "la", "le", "lai", "lei", "lao", "lou", "lan", "lang", "leng", "long",
+    "li", "lia", "lie", "liao", "liu", "lian", "lin", "liang", "ling",
+    "lu", "luo", "lo", "luan", "lun", "lv", "lve",
+
+    "ga", "ge", "gai", "gei", "gao", "gou", "gan", "gen", "gang", "geng", "gong",
+    "gu", "gua", "guo", "guai", "gui", "guan", "gun", "guang",
+    "ka", "ke", "kai", "kei", "kao", "kou", "kan", "ken", "kang", "keng", "kong",
+    "ku", "kua", "kuo", "kuai", "kui", "kuan", "kun", "kuang",
+    "ha", "he", "hai", "hei", "hao", "hou", "han", "hen", "hang", "heng", "hong",
+    "hu", "hua", "huo", "huai", "hui", "huan", "hun", "huang",
+
+    "ji", "jia", "jie", "jiao", "jiu", "jian", "jin", "jiang", "jing", "jiong", "jv", "jve", "jvan", "jvn",
+    "qi", "qia", "qie", "qiao", "qiu", "qian", "qin", "qiang", "qing", "qiong", "qv", "qve", "qvan", "qvn",
+    "xi", "xia", "xie", "xiao", "xiu", "xian", "xin", "xiang", "xing", "xiong", "xv", "xve", "xvan", "xvn",
+
+    "zha", "zhe", "zhi", "zhai", "zhei", "zhao", "zhou", "zhan", "zhen", "zhang", "zheng", "zhong",
+    "zhu", "zhua", "zhuo", "zhuai", "zhui", "zhuan", "zhun", "zhuang",
+    "cha", "che", "chi", "chai", "chao", "chou", "chan", "chen", "chang", "cheng", "chong",
+    "chu", "chua", "chuo", "chuai", "chui", "chuan", "chun", "chuang",
+    "sha", "she", "shi", "shai", "shei", "shao", "shou", "shan", "shen", "shang", "sheng",
+    "shu", "shua", "shuo", "shuai", "shui", "shuan", "shun", "shuang",
+    "re", "ri", "rao", "rou", "ran", "ren", "rang", "reng", "rong",
+    "ru", "rua", "ruo", "rui", "ruan", "run",
+
+    "za", "ze", "zi", "zai", "zei", "zao", "zou", "zan", "zen", "zang", "zeng", "zong",
+    "zu", "zuo", "zui", "zuan", "zun",
+    "ca", "ce", "ci", "cai", "cao", "cou", "can", "cen", "cang", "ceng", "cong",
+    "cu", "cuo", "cui", "cuan", "cun",
+    "sa", "se", "si", "sai", "sao", "sou", "san", "sen", "sang", "seng", "song",
+    "su", "suo", "sui", "suan", "sun",
+];
+
+/// Split a syllable into its initial and its final, undoing the y/w spelling rules
+fn initial_final(syllable: &str) -> (&str, String) {
+    let (initial, rest) = match syllable.as_bytes() {
+        [b'z' | b'c' | b's', b'h', ..] => syllable.split_at(2),
+        [b'y' | b'w', ..] => ("", syllable),
+        b"m" | b"n" | b"ng" => ("", syllable),
+        [c, ..] if !b"aoe".contains(c) => syllable.split_at(1),
+        _ => ("", syllable),
+    };
+
+    let final_ = match rest {
+        "yi" | "yin" | "ying" => rest[1..].to_owned(),
+        "you" => "iu".to_owned(),
+        "yong" => "iong".to_owned(),
+        "wu" => "u".to_owned(),
+        "wei" => "ui".to_owned(),
+        "wen" => "un".to_owned(),
+        "weng" => "ueng".to_owned(),
+        _ if rest.starts_with("yv") => rest[1..].to_owned(),
+        _ if rest.starts_with('y') => format!("i{}", &rest[1..]),
+        _ if rest.starts_with('w') => format!("u{}", &rest[1..]),
+        _ => rest.to_owned(),
+    };
+    (initial, final_)
+}
+
+fn write_syllable_map<W: Write>(file: &mut W) {
+    let mut map = phf_codegen::OrderedMap::new();
+    for syllable in SYLLABLE_LIST {
+        let (initial, final_) = initial_final(syllable);
+        map.entry(*syllable, &format!("({:?}, {:?})", initial, final_));
+    }
+
+    write!(
+        file,
+        "static SYLLABLES: phf::OrderedMap<&str, (&str, &str)> = {}",
+        map.build()
+    )
+    .unwrap();
+    writeln!(file, ";").unwrap();
+}
+
+/// Write the readings of data/hanzi.txt, checking every syllable is in the table
+fn write_hanzi_map<W: Write>(file: &mut W) {
+    let data = fs::read_to_string("data/hanzi.txt").unwrap();
+    let mut map = phf_codegen::Map::new();
+    for (i, line) in data.lines().enumerate() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let parsed = line.split_once('\t').and_then(|(hanzi, reading)| {
+            let mut chars = hanzi.chars();
+            let c = chars.next().filter(|_| chars.as_str().is_empty())?;
+            let syllable = reading.strip_suffix(['1', '2', '3', '4', '5'])?;
+            // The table spells 'ü' as 'v' after 'j', 'q', 'x' and 'y' too
+            let syllable = match syllable.as_bytes() {
+                [b'j' | b'q' | b'x' | b'y', b'u', ..] => syllable.replacen('u', "v", 1),
+                _ => syllable.to_owned(),
+            };
+            SYLLABLE_LIST.contains(&&*syllable).then(|| {
+                let tone = &reading[reading.len() - 1..];
+                (c.to_string(), format!("{}{}", syllable, tone))
+            })
+        });
+        let Some((c, reading)) = parsed else {
+            panic!("data/hanzi.txt:{}: invalid line {:?}", i + 1, line);
+        };
+        map.entry(c, &format!("{:?}", reading));
+    }
+
+    write!(file, "static HANZI: phf::Map<&str, &str> = {}", map.build()).unwrap();
+    writeln!(file, ";").unwrap();
+}
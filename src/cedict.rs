@@ -0,0 +1,257 @@
+//! Word readings from CC-CEDICT.
+//!
+//! Each line of CC-CEDICT is `traditional simplified [pin1 yin1] /gloss/`,
+//! with `u:` for `ü` and capitals for proper nouns. Both the traditional and
+//! the simplified word are indexed. The erhua `r5` (哪儿 [na3 r5]) is read as
+//! a syllable of its own. Entries whose pinyin has something that isn't a
+//! syllable, such as a Latin letter, are skipped.
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::{Lexicon, Syllable};
+
+/// The readings of a word, and whether each is a proper noun
+type Readings = Vec<(Vec<Syllable>, bool)>;
+
+/// Word readings, loaded from a CC-CEDICT file
+///
+/// Readings of a word keep the order of the file, except that proper nouns
+/// come after common words.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let cedict = Cedict::parse("女兒 女儿 [nu:3 er2] /daughter/\n");
+/// assert_eq!(cedict.word_readings("女儿"), ["nv3 er2"]);
+/// assert_eq!(cedict.word_readings("女兒"), ["nv3 er2"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cedict {
+    words: HashMap<String, Readings>,
+    longest_word: usize,
+    skipped: usize,
+}
+
+impl Cedict {
+    /// Create an empty dictionary
+    pub fn new() -> Cedict {
+        Cedict::default()
+    }
+
+    /// Load a CC-CEDICT file
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Cedict> {
+        Cedict::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Load CC-CEDICT from a reader
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Cedict> {
+        let mut ret = Cedict::new();
+        for line in reader.lines() {
+            ret.add_line(&line?);
+        }
+        Ok(ret)
+    }
+
+    /// Load CC-CEDICT from a string
+    pub fn parse(s: &str) -> Cedict {
+        let mut ret = Cedict::new();
+        s.lines().for_each(|line| ret.add_line(line));
+        ret
+    }
+
+    fn add_line(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        let entry = line.split_once(' ').and_then(|(traditional, rest)| {
+            let (simplified, rest) = rest.split_once(' ')?;
+            let (pinyin, _) = rest.strip_prefix('[')?.split_once(']')?;
+            Some((traditional, simplified, pinyin))
+        });
+        let Some((traditional, simplified, pinyin)) = entry else {
+            self.skipped += 1;
+            return;
+        };
+
+        let proper = pinyin.starts_with(char::is_uppercase);
+        let syllables: Option<Vec<Syllable>> = pinyin
+            .split_whitespace()
+            .map(|syllable| {
+                Syllable::new(&syllable.to_lowercase().replace("u:", "v"))
+                    .filter(Syllable::is_word_syllable)
+            })
+            .collect();
+        match syllables {
+            Some(syllables) if syllables.len() == simplified.chars().count() => {
+                self.add(traditional, syllables.clone(), proper);
+                if simplified != traditional {
+                    self.add(simplified, syllables, proper);
+                }
+            }
+            _ => self.skipped += 1,
+        }
+    }
+
+    /// Add a reading of a word, one syllable per character
+    ///
+    /// Readings of proper nouns come after those of common words.
+    pub fn add(&mut self, word: &str, syllables: Vec<Syllable>, proper: bool) {
+        let readings = self.words.entry(word.to_owned()).or_default();
+        if readings.iter().any(|(reading, _)| *reading == syllables) {
+            return;
+        }
+
+        let i = match proper {
+            true => readings.len(),
+            false => readings
+                .iter()
+                .position(|&(_, proper)| proper)
+                .unwrap_or(readings.len()),
+        };
+        readings.insert(i, (syllables, proper));
+        self.longest_word = self.longest_word.max(word.chars().count());
+    }
+
+    /// Returns the number of words with readings
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if no word has a reading
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the number of lines that couldn't be parsed
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns the readings of a word as numbered pinyin, syllables separated by
+    /// spaces
+    ///
+    /// Returns an empty list if the word is unknown.
+    pub fn word_readings(&self, word: &str) -> Vec<String> {
+        self.readings(word)
+            .iter()
+            .map(|reading| {
+                reading
+                    .iter()
+                    .map(Syllable::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+impl Lexicon for Cedict {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        self.words
+            .get(word)
+            .into_iter()
+            .flatten()
+            .map(|(reading, _)| reading.clone())
+            .collect()
+    }
+
+    fn longest_word(&self) -> usize {
+        self.longest_word
+    }
+
+    fn words(&self) -> Vec<&str> {
+        self.words.keys().map(String::as_str).collect()
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    static SAMPLE: &str = "\
+# CC-CEDICT
+#! version=1
+行 行 [hang2] /row/line/
+行 行 [xing2] /to walk/to go/
+銀行 银行 [yin2 hang2] /bank/
+行走 行走 [xing2 zou3] /to walk/
+長 长 [chang2] /length/long/
+長 长 [zhang3] /chief/to grow/
+長城 长城 [Chang2 cheng2] /the Great Wall/
+張 张 [Zhang1] /surname Zhang/
+張 张 [zhang1] /to open up/
+綠 绿 [lu:4] /green/
+哪兒 哪儿 [na3 r5] /where?/
+AA制 AA制 [A A zhi4] /to split the bill/
+卡拉OK 卡拉OK [ka3 la1 O K] /karaoke/
+中國 中国 [Zhong1 guo2] /China/
+人 人 [ren2] /person/
+中國人 中国人 [Zhong1 guo2 ren2] /Chinese person/
+西安 西安 [Xi1 an1] /Xi'an/
+bad line
+";
+
+    #[test]
+    fn cedict_test() {
+        let cedict = Cedict::read(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(cedict, Cedict::parse(SAMPLE));
+        assert_eq!(cedict.skipped(), 3);
+        assert_eq!(cedict.longest_word(), 3);
+        assert_eq!(cedict.len(), 20);
+
+        assert_eq!(cedict.word_readings("行"), ["hang2", "xing2"]);
+        assert_eq!(cedict.word_readings("银行"), ["yin2 hang2"]);
+        assert_eq!(cedict.word_readings("銀行"), ["yin2 hang2"]);
+        assert_eq!(cedict.word_readings("张"), ["zhang1"]);
+        assert_eq!(cedict.word_readings("绿"), ["lv4"]);
+        assert_eq!(cedict.word_readings("哪儿"), ["na3 r5"]);
+        assert!(cedict.word_readings("AA制").is_empty());
+
+        let mut cedict = Cedict::new();
+        assert!(cedict.is_empty());
+        cedict.add("张", vec![Syllable::new("zhang1").unwrap()], true);
+        cedict.add("张", vec![Syllable::new("zhang4").unwrap()], false);
+        cedict.add("张", vec![Syllable::new("zhang4").unwrap()], false);
+        assert_eq!(cedict.word_readings("张"), ["zhang4", "zhang1"]);
+
+        assert!(Cedict::open("/nonexistent/cedict_ts.u8").is_err());
+    }
+
+    #[test]
+    fn annotate_test() {
+        let cedict = Cedict::parse(SAMPLE);
+        let pinyin = |s| crate::annotate_text(s, &cedict, &crate::Pinyin);
+
+        assert_eq!(pinyin("银行"), "yínháng");
+        assert_eq!(pinyin("行走"), "xíngzǒu");
+        assert_eq!(pinyin("中国人"), "zhōngguórén");
+        assert_eq!(pinyin("中国人行走。"), "zhōngguórén xíngzǒu。");
+        assert_eq!(pinyin("西安"), "xī'ān");
+        assert_eq!(pinyin("哪儿"), "nǎr");
+        assert_eq!(pinyin("长城很长"), "chángchéng 很 cháng");
+        assert_eq!(pinyin("AA制 绿"), "AA制 lǜ");
+        assert_eq!(pinyin("绿AA制"), "lǜ AA制");
+        assert_eq!(pinyin(""), "");
+
+        assert_eq!(
+            crate::annotate_text("中国人，银行", &cedict, &crate::Zhuyin),
+            "ㄓㄨㄥ ㄍㄨㄛˊㄖㄣˊ，ㄧㄣˊㄏㄤˊ"
+        );
+        assert_eq!(
+            crate::annotate_text("哪儿", &cedict, &crate::Zhuyin),
+            "ㄋㄚˇㄦ˙"
+        );
+
+        let annotations = crate::annotate("银行很长", &cedict);
+        assert_eq!(annotations.len(), 3);
+        assert_eq!(annotations[0].text, "银行");
+        assert_eq!(annotations[1].text, "很");
+        assert!(!annotations[1].is_annotated());
+        assert_eq!(annotations[2].syllables, [Syllable::new("chang2").unwrap()]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Completing partly typed syllables.
+//!
+//! Every syllable is kept in a table sorted by its spelling, so the syllables
+//! starting with some prefix are one contiguous run, found by binary search.
+use std::sync::OnceLock;
+
+use crate::{encode_pinyin, encode_zhuyin, SYLLABLES};
+
+/// Every syllable by its typed pinyin, spelt both with `v` and, after j, q, x
+/// and y, with `u`, sorted by spelling
+fn pinyin_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut ret = Vec::with_capacity(SYLLABLES.len() + 32);
+        for &syllable in SYLLABLES.keys() {
+            ret.push((syllable.to_owned(), syllable));
+            if syllable.starts_with(['j', 'q', 'x', 'y']) && syllable.contains('v') {
+                ret.push((syllable.replace('v', "u"), syllable));
+            }
+        }
+        ret.sort();
+        ret
+    })
+}
+
+/// Every syllable by its zhuyin without a tone mark, sorted by zhuyin
+fn zhuyin_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut ret: Vec<_> = SYLLABLES
+            .keys()
+            .filter_map(|&syllable| Some((encode_zhuyin(format!("{}1", syllable))?, syllable)))
+            .collect();
+        ret.sort();
+        ret
+    })
+}
+
+/// Returns the syllables whose spelling starts with a prefix, in spelling order
+fn complete(table: &'static [(String, &'static str)], prefix: &str) -> Vec<&'static str> {
+    let start = table.partition_point(|(spelling, _)| spelling.as_str() < prefix);
+    let mut ret: Vec<&'static str> = Vec::new();
+    for (_, syllable) in table[start..]
+        .iter()
+        .take_while(|(spelling, _)| spelling.starts_with(prefix))
+    {
+        if !ret.contains(syllable) {
+            ret.push(syllable);
+        }
+    }
+    ret
+}
+
+/// Complete partly typed pinyin
+///
+/// The prefix is toneless; `ü` may be typed as `v`. Completions are pinyin
+/// with the given tone, or without a tone mark if None, in alphabetical order.
+/// Returns an empty list if nothing completes the prefix.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     complete_pinyin("zhua", None),
+///     ["zhua", "zhuai", "zhuan", "zhuang"]
+/// );
+/// assert_eq!(complete_pinyin("lü", Some(4)), ["lǜ", "lüè"]);
+/// assert_eq!(complete_pinyin("ju", Some(2)), ["jú", "juán", "jué", "jún"]);
+/// ```
+pub fn complete_pinyin<S>(prefix: S, tone: Option<u8>) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    let prefix = prefix.as_ref().trim().to_lowercase().replace('ü', "v");
+    if prefix.is_empty() || tone.is_some_and(|tone| !(1..=5).contains(&tone)) {
+        return Vec::new();
+    }
+
+    complete(pinyin_table(), &prefix)
+        .iter()
+        .filter_map(|&syllable| match (syllable, tone.unwrap_or(5)) {
+            // `encode_pinyin` gives ê for e5
+            ("e", 5) => Some("e".to_owned()),
+            (syllable, tone) => encode_pinyin(format!("{}{}", syllable, tone)),
+        })
+        .collect()
+}
+
+/// Complete partly typed zhuyin
+///
+/// The prefix has no tone mark. Completions are zhuyin with the given tone, or
+/// without a tone mark if None, in the order of the zhuyin symbols. Returns an
+/// empty list if nothing completes the prefix.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     complete_zhuyin("ㄒㄩ", None),
+///     ["ㄒㄩ", "ㄒㄩㄝ", "ㄒㄩㄢ", "ㄒㄩㄣ", "ㄒㄩㄥ"]
+/// );
+/// assert_eq!(complete_zhuyin("ㄌㄩ", Some(4)), ["ㄌㄩˋ", "ㄌㄩㄝˋ"]);
+/// ```
+pub fn complete_zhuyin<S>(prefix: S, tone: Option<u8>) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    let prefix = prefix.as_ref().trim();
+    if prefix.is_empty() || tone.is_some_and(|tone| !(1..=5).contains(&tone)) {
+        return Vec::new();
+    }
+
+    complete(zhuyin_table(), prefix)
+        .iter()
+        .filter_map(|syllable| encode_zhuyin(format!("{}{}", syllable, tone.unwrap_or(1))))
+        .collect()
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn complete_pinyin_test() {
+        assert_eq!(
+            complete_pinyin("zhu", None),
+            ["zhu", "zhua", "zhuai", "zhuan", "zhuang", "zhui", "zhun", "zhuo"]
+        );
+        assert_eq!(
+            complete_pinyin("Xi", None),
+            ["xi", "xia", "xian", "xiang", "xiao", "xie", "xin", "xing", "xiong", "xiu"]
+        );
+        assert_eq!(complete_pinyin("xu", None), ["xu", "xuan", "xue", "xun"]);
+        assert_eq!(complete_pinyin("xv", None), ["xu", "xuan", "xue", "xun"]);
+        assert_eq!(complete_pinyin("lv", None), ["lü", "lüe"]);
+        assert_eq!(complete_pinyin("lü", None), ["lü", "lüe"]);
+        assert_eq!(complete_pinyin("zhuang", Some(1)), ["zhuāng"]);
+        assert_eq!(complete_pinyin("er", Some(5)), ["er"]);
+        assert_eq!(complete_pinyin("e", None), ["e", "ei", "en", "eng", "er"]);
+        assert_eq!(complete_pinyin("z", None).len(), 37);
+
+        assert!(complete_pinyin("zhuu", None).is_empty());
+        assert!(complete_pinyin("zhu", Some(6)).is_empty());
+        assert!(complete_pinyin("", None).is_empty());
+    }
+
+    #[test]
+    fn complete_zhuyin_test() {
+        assert_eq!(complete_zhuyin("ㄓㄨ", None).len(), 9);
+        assert_eq!(complete_zhuyin("ㄓㄨ", Some(3))[0], "ㄓㄨˇ");
+        assert_eq!(complete_zhuyin("ㄦ", Some(5)), ["ㄦ˙"]);
+        assert_eq!(complete_zhuyin("ㄧ", None).len(), 10);
+        assert_eq!(
+            complete_zhuyin("ㄒㄩ", None),
+            ["ㄒㄩ", "ㄒㄩㄝ", "ㄒㄩㄢ", "ㄒㄩㄣ", "ㄒㄩㄥ"]
+        );
+
+        assert!(complete_zhuyin("ㄓㄧ", None).is_empty());
+        assert!(complete_zhuyin("ㄓㄨ", Some(0)).is_empty());
+        assert!(complete_zhuyin("", None).is_empty());
+    }
+}
@@ -0,0 +1,429 @@
+//! A composing buffer for input methods.
+//!
+//! In zhuyin mode each symbol belongs to a slot (initial, medial or final), and
+//! typing a symbol whose slot is taken replaces it, as zhuyin input methods do.
+//! The keys typed are kept, so keys with several symbols (on Hsu) are read by
+//! whatever still leads to a syllable. In pinyin mode the buffer must stay the
+//! start of some syllable. A tone key or space commits the syllable.
+use std::sync::OnceLock;
+
+use crate::keyboard::is_syllable;
+use crate::{
+    encode_zhuyin, is_nasal, lookup_syllable, KeyboardLayout, Notation, Syllable, Zhuyin, SYLLABLES,
+};
+
+/// The zhuyin symbols of a syllable by slot: initial, medial and final
+type Slots = [Option<char>; 3];
+
+/// What the composer types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComposerMode {
+    /// Zhuyin keys on a keyboard layout; space is the first tone
+    Zhuyin(KeyboardLayout),
+    /// Pinyin letters, with `v` for `ü`; `1` to `5` are tones, and space commits with the 5th tone
+    Pinyin,
+}
+
+/// What happened to a key given to the composer
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KeyResult {
+    /// The key can't be typed here, and nothing changed
+    Rejected,
+    /// The key was added to the syllable being composed
+    Composing,
+    /// The key finished a syllable, and the composer is empty again
+    Committed(Syllable),
+}
+
+/// A composing buffer that takes keys one at a time
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let mut composer = Composer::new(ComposerMode::Zhuyin(KeyboardLayout::Dachen));
+/// assert_eq!(composer.input('s'), KeyResult::Composing);
+/// assert_eq!(composer.input('u'), KeyResult::Composing);
+/// assert_eq!(composer.preedit(), "ㄋㄧ");
+/// assert_eq!(composer.input('3'), KeyResult::Committed(Syllable::new("ni3").unwrap()));
+/// assert!(composer.is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Composer {
+    mode: ComposerMode,
+    /// Zhuyin keys, in the order typed
+    keys: Vec<char>,
+    /// Pinyin letters
+    pinyin: String,
+}
+
+impl Composer {
+    /// Create an empty composer
+    pub fn new(mode: ComposerMode) -> Composer {
+        Composer {
+            mode,
+            keys: Vec::new(),
+            pinyin: String::new(),
+        }
+    }
+
+    /// Returns the mode
+    pub fn mode(&self) -> ComposerMode {
+        self.mode
+    }
+
+    /// Returns true if nothing is being composed
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.pinyin.is_empty()
+    }
+
+    /// Drop the syllable being composed
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.pinyin.clear();
+    }
+
+    /// Remove the last key typed
+    ///
+    /// Returns false if the composer was empty.
+    pub fn backspace(&mut self) -> bool {
+        match self.mode {
+            ComposerMode::Zhuyin(_) => self.keys.pop().is_some(),
+            ComposerMode::Pinyin => self.pinyin.pop().is_some(),
+        }
+    }
+
+    /// Type a key
+    pub fn input(&mut self, key: char) -> KeyResult {
+        let key = key.to_ascii_lowercase();
+        match self.mode {
+            ComposerMode::Zhuyin(layout) => self.input_zhuyin(key, layout),
+            ComposerMode::Pinyin => self.input_pinyin(key),
+        }
+    }
+
+    /// Returns what is being composed, as zhuyin in zhuyin mode and pinyin in pinyin mode
+    pub fn preedit(&self) -> String {
+        match self.mode {
+            ComposerMode::Zhuyin(layout) => readings(&self.keys, layout)
+                .first()
+                .map(|slots| slots.iter().flatten().collect())
+                .unwrap_or_default(),
+            ComposerMode::Pinyin => self.pinyin.clone(),
+        }
+    }
+
+    /// Returns what is being composed as zhuyin
+    ///
+    /// In pinyin mode, returns None until the letters spell a whole syllable.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let mut composer = Composer::new(ComposerMode::Pinyin);
+    /// "zhu".chars().for_each(|c| { composer.input(c); });
+    /// assert_eq!(composer.zhuyin(), Some("ㄓㄨ".to_owned()));
+    /// composer.backspace();
+    /// assert_eq!(composer.zhuyin(), None);
+    /// ```
+    pub fn zhuyin(&self) -> Option<String> {
+        match self.mode {
+            ComposerMode::Zhuyin(_) => Some(self.preedit()),
+            ComposerMode::Pinyin => encode_zhuyin(format!("{}1", self.pinyin)),
+        }
+    }
+
+    fn input_zhuyin(&mut self, key: char, layout: KeyboardLayout) -> KeyResult {
+        let symbols = layout.symbols(key);
+
+        // Tones only count once something has been typed, so Hsu's `d` can be ㄉ
+        if !self.keys.is_empty() {
+            let tone = match key {
+                ' ' => Some(String::new()),
+                _ => symbols
+                    .iter()
+                    .find(|&&c| "˙ˊˇˋ".contains(c))
+                    .map(|&c| c.to_string()),
+            };
+            if let Some(tone) = tone {
+                return self.commit_zhuyin(&tone, layout);
+            }
+        }
+
+        // Add the key as a new symbol
+        self.keys.push(key);
+        if !readings(&self.keys, layout).is_empty() {
+            return KeyResult::Composing;
+        }
+        self.keys.pop();
+
+        // Or replace the key whose slot it takes
+        let Some(current) = readings(&self.keys, layout).into_iter().next() else {
+            return KeyResult::Rejected;
+        };
+        for symbol in symbols {
+            let Some(slot) = slot(symbol) else {
+                continue;
+            };
+            let Some(i) = self.keys.iter().position(|&k| {
+                let symbols = layout.symbols(k);
+                current[slot].is_some_and(|c| symbols.contains(&c))
+            }) else {
+                continue;
+            };
+
+            let mut keys = self.keys.clone();
+            keys.remove(i);
+            keys.push(key);
+            if !readings(&keys, layout).is_empty() {
+                self.keys = keys;
+                return KeyResult::Composing;
+            }
+        }
+        KeyResult::Rejected
+    }
+
+    fn commit_zhuyin(&mut self, tone: &str, layout: KeyboardLayout) -> KeyResult {
+        let zhuyin = readings(&self.keys, layout)
+            .into_iter()
+            .map(|slots| slots.iter().flatten().collect::<String>() + tone)
+            .find(|zhuyin| is_syllable(zhuyin));
+
+        match zhuyin.and_then(|zhuyin| Zhuyin.parse(&zhuyin)) {
+            Some(syllable) => {
+                self.clear();
+                KeyResult::Committed(syllable)
+            }
+            None => KeyResult::Rejected,
+        }
+    }
+
+    fn input_pinyin(&mut self, key: char) -> KeyResult {
+        let tone = match key {
+            ' ' => Some(5),
+            '1'..='5' => key.to_digit(10).map(|tone| tone as u8),
+            _ => None,
+        };
+        if let Some(tone) = tone {
+            return match lookup_syllable(&format!("{}{}", self.pinyin, tone)) {
+                Some((syllable, _, _, tone)) => {
+                    let syllable = Syllable::with_tone(syllable, tone).unwrap();
+                    self.clear();
+                    KeyResult::Committed(syllable)
+                }
+                None => KeyResult::Rejected,
+            };
+        }
+
+        let key = if key == 'ü' { 'v' } else { key };
+        if !key.is_ascii_lowercase() {
+            return KeyResult::Rejected;
+        }
+        self.pinyin.push(key);
+        if pinyin_spellings()
+            .iter()
+            .any(|spelling| spelling.starts_with(&self.pinyin))
+        {
+            return KeyResult::Composing;
+        }
+        self.pinyin.pop();
+        KeyResult::Rejected
+    }
+}
+
+/// Returns the slot of a zhuyin symbol, or None for a tone mark
+fn slot(symbol: char) -> Option<usize> {
+    match symbol {
+        'ㄅ'..='ㄙ' => Some(0),
+        'ㄧ' | 'ㄨ' | 'ㄩ' => Some(1),
+        'ㄚ'..='ㄦ' => Some(2),
+        _ => None,
+    }
+}
+
+/// Every syllable in zhuyin slots
+fn syllable_slots() -> &'static [Slots] {
+    static SLOTS: OnceLock<Vec<Slots>> = OnceLock::new();
+    SLOTS.get_or_init(|| {
+        // Syllabic nasals aren't composed, and ㄫ has no slot
+        SYLLABLES
+            .keys()
+            .filter(|syllable| !is_nasal(syllable))
+            .filter_map(|syllable| encode_zhuyin(format!("{}1", syllable)))
+            .map(|zhuyin| {
+                let mut slots = [None; 3];
+                for c in zhuyin.chars() {
+                    slots[slot(c).unwrap()] = Some(c);
+                }
+                slots
+            })
+            .collect()
+    })
+}
+
+/// Every syllable in pinyin, spelt both with `v` and, after j, q, x and y, with `u`
+fn pinyin_spellings() -> &'static [String] {
+    static SPELLINGS: OnceLock<Vec<String>> = OnceLock::new();
+    SPELLINGS.get_or_init(|| {
+        let mut ret = Vec::with_capacity(SYLLABLES.len() + 32);
+        for syllable in SYLLABLES.keys() {
+            ret.push(syllable.to_string());
+            if syllable.starts_with(['j', 'q', 'x', 'y']) && syllable.contains('v') {
+                ret.push(syllable.replace('v', "u"));
+            }
+        }
+        ret
+    })
+}
+
+/// Every way of reading the keys as slots that some syllable fills, most likely first
+fn readings(keys: &[char], layout: KeyboardLayout) -> Vec<Slots> {
+    let mut ret = Vec::new();
+    read(keys, layout, [None; 3], &mut ret);
+    ret
+}
+
+fn read(keys: &[char], layout: KeyboardLayout, slots: Slots, ret: &mut Vec<Slots>) {
+    let Some((&key, rest)) = keys.split_first() else {
+        let fits = syllable_slots()
+            .iter()
+            .any(|syllable| (0..3).all(|i| slots[i].is_none() || slots[i] == syllable[i]));
+        if fits && !ret.contains(&slots) {
+            ret.push(slots);
+        }
+        return;
+    };
+
+    for symbol in layout.symbols(key) {
+        if let Some(i) = slot(symbol).filter(|&i| slots[i].is_none()) {
+            let mut slots = slots;
+            slots[i] = Some(symbol);
+            read(rest, layout, slots, ret);
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn type_keys(composer: &mut Composer, keys: &str) -> Vec<KeyResult> {
+        keys.chars().map(|key| composer.input(key)).collect()
+    }
+
+    #[allow(dead_code)]
+    fn committed(s: &str) -> KeyResult {
+        KeyResult::Committed(Syllable::new(s).unwrap())
+    }
+
+    #[test]
+    fn zhuyin_composer_test() {
+        use KeyResult::*;
+
+        let mut composer = Composer::new(ComposerMode::Zhuyin(KeyboardLayout::Dachen));
+        assert!(composer.is_empty());
+        assert_eq!(type_keys(&mut composer, "5j/"), vec![Composing; 3]);
+        assert_eq!(composer.preedit(), "ㄓㄨㄥ");
+        assert_eq!(composer.input(' '), committed("zhong1"));
+        assert_eq!(composer.preedit(), "");
+
+        // A symbol replaces the one in its slot
+        type_keys(&mut composer, "18");
+        assert_eq!(composer.input('i'), Composing);
+        assert_eq!(composer.preedit(), "ㄅㄛ");
+        assert_eq!(composer.input('q'), Composing);
+        assert_eq!(composer.preedit(), "ㄆㄛ");
+        // A medial goes before the final whenever it's typed
+        composer.clear();
+        type_keys(&mut composer, "28u");
+        assert_eq!(composer.preedit(), "ㄉㄧㄚ");
+        // Nothing can make ㄈㄧ
+        composer.clear();
+        type_keys(&mut composer, "z");
+        assert_eq!(composer.input('u'), Rejected);
+        assert_eq!(composer.preedit(), "ㄈ");
+
+        // Tones
+        assert_eq!(composer.input('6'), Rejected);
+        assert!(composer.backspace());
+        assert!(!composer.backspace());
+        assert_eq!(composer.input('3'), Rejected);
+        assert_eq!(composer.input(' '), Rejected);
+        type_keys(&mut composer, "ap");
+        assert_eq!(composer.input('7'), committed("men5"));
+        type_keys(&mut composer, "u");
+        assert_eq!(composer.input('4'), committed("yi4"));
+        type_keys(&mut composer, "-");
+        assert_eq!(composer.input('7'), committed("r5"));
+
+        // Backspace removes the last key
+        type_keys(&mut composer, "cl");
+        assert!(composer.backspace());
+        assert_eq!(composer.preedit(), "ㄏ");
+        assert_eq!(composer.input(' '), Rejected);
+    }
+
+    #[test]
+    fn hsu_composer_test() {
+        use KeyResult::*;
+
+        let mut composer = Composer::new(ComposerMode::Zhuyin(KeyboardLayout::Hsu));
+        assert_eq!(type_keys(&mut composer, "jx"), vec![Composing; 2]);
+        assert_eq!(composer.preedit(), "ㄓㄨ");
+        assert_eq!(composer.input('l'), Composing);
+        assert_eq!(composer.zhuyin(), Some("ㄓㄨㄥ".to_owned()));
+        assert_eq!(composer.input(' '), committed("zhong1"));
+
+        composer.input('l');
+        assert_eq!(composer.preedit(), "ㄦ");
+        composer.input('a');
+        assert_eq!(composer.preedit(), "ㄌㄟ");
+        assert_eq!(composer.input('j'), committed("lei4"));
+
+        // `d` is ㄉ at the start and the 2nd tone after it
+        assert_eq!(
+            type_keys(&mut composer, "dyd"),
+            [Composing, Composing, committed("da2")]
+        );
+        assert_eq!(
+            type_keys(&mut composer, "jef"),
+            [Composing, Composing, committed("ji3")]
+        );
+    }
+
+    #[test]
+    fn pinyin_composer_test() {
+        use KeyResult::*;
+
+        let mut composer = Composer::new(ComposerMode::Pinyin);
+        assert_eq!(type_keys(&mut composer, "Zhuang"), vec![Composing; 6]);
+        assert_eq!(composer.preedit(), "zhuang");
+        assert_eq!(composer.input('x'), Rejected);
+        assert_eq!(composer.input('1'), committed("zhuang1"));
+
+        type_keys(&mut composer, "lv");
+        assert_eq!(composer.input('e'), Composing);
+        assert_eq!(composer.input('4'), committed("lve4"));
+        type_keys(&mut composer, "xu");
+        assert_eq!(composer.zhuyin(), Some("ㄒㄩ".to_owned()));
+        assert_eq!(composer.input('e'), Composing);
+        assert_eq!(composer.input('2'), committed("xve2"));
+        type_keys(&mut composer, "nü");
+        assert_eq!(composer.preedit(), "nv");
+        assert_eq!(composer.input(' '), committed("nv5"));
+
+        // Only whole syllables commit
+        type_keys(&mut composer, "zh");
+        assert_eq!(composer.input('3'), Rejected);
+        assert_eq!(composer.zhuyin(), None);
+        assert!(composer.backspace());
+        assert_eq!(composer.preedit(), "z");
+        assert_eq!(composer.input('3'), Rejected);
+        assert_eq!(composer.input('i'), Composing);
+        assert_eq!(composer.input('3'), committed("zi3"));
+
+        assert_eq!(composer.input('1'), Rejected);
+        assert_eq!(composer.input('-'), Rejected);
+        assert_eq!(composer.input('v'), Rejected);
+    }
+}
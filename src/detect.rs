@@ -0,0 +1,391 @@
+//! Guessing the notation of a token.
+//!
+//! Each notation that can be parsed tries the token, and every valid reading it
+//! gives is a candidate. Notations in their own script, or with a tone, are
+//! strong evidence; the toneless Latin notations often read the same token, so
+//! they weigh less. The weights are shared out and normalized to confidences.
+//!
+//! A token may hold several syllables. Pinyin, numbered pinyin, zhuyin and
+//! Palladius are split into syllables as they are written together; the other
+//! notations need the syllables separated by hyphens or spaces (Pei-ching), so
+//! `beijing` is only read as pinyin and `Běijīng` gives no Yale reading.
+use crate::{
+    decode_hangul, decode_latinxua, decode_palladius_word, split_numbered_word, split_zhuyin_word,
+    Lattice, Notation, Syllable,
+};
+
+/// A notation that `detect_notation` can recognize
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotationKind {
+    /// Numbered pinyin, such as `zhang1`
+    NumberedPinyin,
+    /// Pinyin with tone marks, or without a tone
+    Pinyin,
+    /// Zhuyin
+    Zhuyin,
+    /// Palladius Cyrillic
+    Palladius,
+    /// Korean Hangul
+    Hangul,
+    /// Latinxua Sin Wenz
+    Latinxua,
+    /// EFEO French romanization
+    Efeo,
+    /// Wade-Giles
+    WadeGiles,
+    /// Tongyong pinyin
+    Tongyong,
+    /// Yale romanization
+    Yale,
+    /// Mainland Chinese Braille
+    MainlandBraille,
+    /// Taiwanese Braille
+    TaiwanBraille,
+}
+
+use NotationKind::*;
+
+static KINDS: [NotationKind; 12] = [
+    NumberedPinyin,
+    Pinyin,
+    Zhuyin,
+    Palladius,
+    Hangul,
+    Latinxua,
+    Efeo,
+    WadeGiles,
+    Tongyong,
+    Yale,
+    MainlandBraille,
+    TaiwanBraille,
+];
+
+impl NotationKind {
+    /// Returns the notation itself, to parse or render with
+    pub fn notation(self) -> &'static dyn Notation {
+        match self {
+            NumberedPinyin => &crate::NumberedPinyin,
+            Pinyin => &crate::Pinyin,
+            Zhuyin => &crate::Zhuyin,
+            Palladius => &crate::Palladius,
+            Hangul => &crate::Hangul,
+            Latinxua => &crate::Latinxua(crate::Palatals::Rounded),
+            Efeo => &crate::Efeo,
+            WadeGiles => &crate::WadeGiles,
+            Tongyong => &crate::Tongyong,
+            Yale => &crate::Yale,
+            MainlandBraille => &crate::MainlandBraille,
+            TaiwanBraille => &crate::TaiwanBraille,
+        }
+    }
+
+    /// How strongly a successful parse points to this notation
+    fn weight(self) -> f32 {
+        match self {
+            Latinxua | Efeo | WadeGiles | Tongyong | Yale => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    /// Every valid reading this notation gives the token
+    ///
+    /// A token of one part may have several readings; a token of several parts
+    /// separated by hyphens or spaces has the first reading of each.
+    fn candidates(self, s: &str) -> Vec<Vec<Syllable>> {
+        if self == Pinyin {
+            return self.part_candidates(&s.replace('-', " "));
+        }
+
+        let parts: Vec<&str> = s
+            .split(|c: char| c == '-' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        match parts[..] {
+            [] => Vec::new(),
+            [part] => self.part_candidates(part),
+            _ => parts
+                .iter()
+                .map(|part| self.part_candidates(part).into_iter().next())
+                .collect::<Option<Vec<_>>>()
+                .map(|readings| vec![readings.concat()])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Every valid reading this notation gives a part of a token
+    fn part_candidates(self, s: &str) -> Vec<Vec<Syllable>> {
+        let syllables = |syllables: Vec<String>| -> Vec<Vec<Syllable>> {
+            syllables
+                .iter()
+                .flat_map(|s| Syllable::new(s))
+                .map(|s| vec![s])
+                .collect()
+        };
+        let candidates = match self {
+            Pinyin => match self.notation().parse(s) {
+                Some(syllable) => vec![vec![syllable]],
+                None => {
+                    let lattice = Lattice::new(s, false);
+                    lattice
+                        .paths(1)
+                        .iter()
+                        .map(|path| path.iter().map(|edge| edge.syllables[0].clone()).collect())
+                        .collect()
+                }
+            },
+            NumberedPinyin => split_numbered_word(s)
+                .and_then(|parts| parts.iter().map(|part| Syllable::new(part)).collect())
+                .into_iter()
+                .collect(),
+            Zhuyin => split_zhuyin_word(s)
+                .and_then(|parts| parts.iter().map(|part| crate::Zhuyin.parse(part)).collect())
+                .into_iter()
+                .collect(),
+            Palladius => decode_palladius_word(s)
+                .and_then(|parts| parts.iter().map(|part| Syllable::new(part)).collect())
+                .into_iter()
+                .collect(),
+            Hangul => syllables(decode_hangul(s)),
+            Latinxua => syllables(decode_latinxua(s)),
+            _ => self
+                .notation()
+                .parse(s)
+                .map(|s| vec![s])
+                .into_iter()
+                .collect(),
+        };
+        candidates
+            .into_iter()
+            .filter(|reading: &Vec<Syllable>| {
+                !reading.is_empty() && reading.iter().all(Syllable::is_valid)
+            })
+            .collect()
+    }
+}
+
+/// A possible reading of a token
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    /// The notation the token was read in
+    pub notation: NotationKind,
+    /// The syllables read
+    pub syllables: Vec<Syllable>,
+    /// How likely this reading is, from 0 to 1
+    pub confidence: f32,
+}
+
+/// The most likely reading of a token, with the others
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseAny {
+    /// The most likely reading
+    pub best: Detection,
+    /// The other readings, most likely first
+    pub alternatives: Vec<Detection>,
+}
+
+/// Read a token of one or more syllables in every notation that accepts it
+///
+/// The token may be capitalized. Only the readings with the fewest syllables
+/// are given. The confidences add up to 1, and the most likely reading comes
+/// first. Returns an empty list if no notation accepts it.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let detections = detect_notation("ㄇㄚˇ");
+/// assert_eq!(detections.len(), 1);
+/// assert_eq!(detections[0].notation, NotationKind::Zhuyin);
+/// assert_eq!(detections[0].confidence, 1.0);
+///
+/// let detections = detect_notation("ma");
+/// assert_eq!(detections[0].notation, NotationKind::Pinyin);
+/// assert_eq!(detections[1].notation, NotationKind::Latinxua);
+/// ```
+pub fn detect_notation<S>(s: S) -> Vec<Detection>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().trim().to_lowercase();
+
+    let readings = KINDS.map(|kind| (kind, kind.candidates(&s)));
+    // One syllable of a notation often reads as two of pinyin, such as EFEO
+    // `kouo` as `kou o`, so only the readings with the fewest syllables count
+    let fewest = readings
+        .iter()
+        .flat_map(|(_, candidates)| candidates.iter().map(Vec::len))
+        .min();
+
+    let mut ret = Vec::new();
+    for (kind, mut candidates) in readings {
+        candidates.retain(|syllables| Some(syllables.len()) == fewest);
+        let weight = kind.weight() / candidates.len() as f32;
+        ret.extend(candidates.into_iter().map(|syllables| Detection {
+            notation: kind,
+            syllables,
+            confidence: weight,
+        }));
+    }
+
+    let total: f32 = ret.iter().map(|detection| detection.confidence).sum();
+    ret.iter_mut()
+        .for_each(|detection| detection.confidence /= total);
+    // Stable, so ties keep the order of `NotationKind`
+    ret.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    ret
+}
+
+/// Read a token of one or more syllables in whichever notation is most likely
+///
+/// Returns None if no notation accepts it.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let parse = parse_any("tch'ong").unwrap();
+/// assert_eq!(parse.best.notation, NotationKind::Efeo);
+/// assert_eq!(parse.best.syllables, [Syllable::new("chong5").unwrap()]);
+/// assert!(parse.alternatives.is_empty());
+/// ```
+pub fn parse_any<S>(s: S) -> Option<ParseAny>
+where
+    S: AsRef<str>,
+{
+    let mut detections = detect_notation(s).into_iter();
+    Some(ParseAny {
+        best: detections.next()?,
+        alternatives: detections.collect(),
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn readings(s: &str) -> Vec<(NotationKind, String)> {
+        detect_notation(s)
+            .into_iter()
+            .map(|detection| {
+                let syllables: Vec<String> = detection
+                    .syllables
+                    .iter()
+                    .map(Syllable::to_string)
+                    .collect();
+                (detection.notation, syllables.join(" "))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detect_notation_test() {
+        assert_eq!(
+            readings("ma3"),
+            [
+                (NumberedPinyin, "ma3".to_owned()),
+                (WadeGiles, "ma3".to_owned())
+            ]
+        );
+        assert_eq!(
+            readings("mǎ"),
+            [
+                (Pinyin, "ma3".to_owned()),
+                (Tongyong, "ma3".to_owned()),
+                (Yale, "ma3".to_owned())
+            ]
+        );
+        assert_eq!(readings("ㄇㄚˇ"), [(Zhuyin, "ma3".to_owned())]);
+        assert_eq!(readings("Мао"), [(Palladius, "mao5".to_owned())]);
+        assert_eq!(readings("xao"), [(Latinxua, "hao5".to_owned())]);
+        assert_eq!(readings("k'ing"), [(Efeo, "qing5".to_owned())]);
+        assert_eq!(readings("ch'ing¹"), [(WadeGiles, "qing1".to_owned())]);
+        assert_eq!(readings("jhong"), [(Tongyong, "zhong1".to_owned())]);
+        assert_eq!(readings("gwó"), [(Yale, "guo2".to_owned())]);
+        assert_eq!(readings("⠁⠯⠄"), [(TaiwanBraille, "zhong1".to_owned())]);
+        assert_eq!(
+            readings("주"),
+            [(Hangul, "jiu5".to_owned()), (Hangul, "zhu5".to_owned())]
+        );
+        assert_eq!(
+            readings("Mao"),
+            [
+                (Pinyin, "mao5".to_owned()),
+                (Latinxua, "mao5".to_owned()),
+                (Efeo, "mao5".to_owned()),
+                (WadeGiles, "mao5".to_owned()),
+                (Tongyong, "mao1".to_owned())
+            ]
+        );
+
+        let detections = detect_notation("mao");
+        assert_eq!(detections[0].confidence, 1.0 / 3.0);
+        assert_eq!(detections[1].confidence, 1.0 / 6.0);
+        let total: f32 = detect_notation("주").iter().map(|d| d.confidence).sum();
+        assert_eq!(total, 1.0);
+
+        assert!(detect_notation("r5").is_empty());
+        assert!(detect_notation("").is_empty());
+    }
+
+    #[test]
+    fn detect_words_test() {
+        // Written together, as pinyin and scripts split into syllables
+        assert_eq!(readings("beijing"), [(Pinyin, "bei5 jing5".to_owned())]);
+        assert_eq!(readings("Běijīng"), [(Pinyin, "bei3 jing1".to_owned())]);
+        assert_eq!(readings("xi'an"), [(Pinyin, "xi5 an5".to_owned())]);
+        assert_eq!(
+            readings("zhong1guo2"),
+            [(NumberedPinyin, "zhong1 guo2".to_owned())]
+        );
+        assert_eq!(
+            readings("ㄓㄨㄥㄍㄨㄛˊ"),
+            [(Zhuyin, "zhong1 guo2".to_owned())]
+        );
+        assert_eq!(readings("Бэйцзин"), [(Palladius, "bei5 jing5".to_owned())]);
+        // Separated, as the other notations need
+        assert_eq!(
+            readings("Mao Tsê-tung"),
+            [(WadeGiles, "mao5 ze5 dong5".to_owned())]
+        );
+        assert_eq!(
+            readings("Pei-ching"),
+            [
+                (WadeGiles, "bei5 jing5".to_owned()),
+                (Yale, "pei5 qing5".to_owned())
+            ]
+        );
+        assert_eq!(
+            readings("jhong-shan"),
+            [(Tongyong, "zhong1 shan1".to_owned())]
+        );
+        // Not separated, so only read as one syllable or as pinyin
+        assert!(readings("peiching").is_empty());
+        assert!(readings("jhongshan").is_empty());
+        assert!(readings("gwójyā").is_empty());
+        // A syllable of EFEO isn't read as two of pinyin
+        assert_eq!(readings("kouo"), [(Efeo, "guo5".to_owned())]);
+    }
+
+    #[test]
+    fn parse_any_test() {
+        let parse = parse_any(" Zhāng ").unwrap();
+        assert_eq!(parse.best.notation, Pinyin);
+        assert_eq!(parse.best.syllables, [Syllable::new("zhang1").unwrap()]);
+        assert_eq!(parse.best.confidence, 1.0);
+        assert!(parse.alternatives.is_empty());
+
+        let parse = parse_any("lo").unwrap();
+        assert_eq!(parse.best.notation, Pinyin);
+        assert_eq!(parse.best.confidence, 1.0 / 3.5);
+        assert_eq!(parse.alternatives.len(), 6);
+        assert_eq!(parse.alternatives[0].notation, Efeo);
+        assert_eq!(parse.alternatives[1].notation, WadeGiles);
+        assert_eq!(parse.alternatives[4].notation, Latinxua);
+        assert_eq!(parse.alternatives[4].syllables[0].to_string(), "luo5");
+
+        let parse = parse_any("Pei-ching").unwrap();
+        assert_eq!(parse.best.notation, WadeGiles);
+        assert_eq!(parse.best.syllables.len(), 2);
+
+        assert_eq!(parse_any("q"), None);
+    }
+}
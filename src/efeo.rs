@@ -0,0 +1,450 @@
+//! EFEO French romanization.
+//!
+//! The École française d'Extrême-Orient system spells Mandarin for French
+//! readers (Tch'ong-k'ing, Mao Tsö-tong): aspiration is an apostrophe, `u` is
+//! `ou`, `ü` is `iu`, and `j`, `q`, `x` share `k`, `k'`, `h` with `g`, `k`,
+//! `h`. It drops the tone, and the syllables of a name are joined by hyphens.
+//!
+//! EFEO writes `j`, `q`, `x` as `ts`, `ts'`, `s` where older Mandarin had
+//! dental initials (Ts'ing, Si-ngan). That depends on the character, not the
+//! syllable, so encoding always gives `k`, `k'`, `h`, and decoding reads both.
+use std::sync::OnceLock;
+
+use crate::{
+    decode_pinyin, decode_zhuyin, encode_pinyin, lookup_syllable, Notation, Syllable, SYLLABLES,
+};
+
+/// EFEO, such as `tchang`
+///
+/// Parsing gives the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Efeo;
+
+impl Notation for Efeo {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_efeo(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_efeo(syllable.to_string())
+    }
+}
+
+/// Returns the EFEO spelling of an initial
+fn initial(initial: &str) -> &'static str {
+    match initial {
+        "b" => "p",
+        "p" => "p'",
+        "m" => "m",
+        "f" => "f",
+        "d" => "t",
+        "t" => "t'",
+        "n" => "n",
+        "l" => "l",
+        "g" | "j" => "k",
+        "k" | "q" => "k'",
+        "h" | "x" => "h",
+        "zh" => "tch",
+        "ch" => "tch'",
+        "sh" => "ch",
+        "r" => "j",
+        "z" => "ts",
+        "c" => "ts'",
+        "s" => "s",
+        _ => "",
+    }
+}
+
+/// Returns the EFEO spelling of a final, which depends on the initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("zh" | "ch" | "sh" | "r", "i") => "e",
+        ("z" | "c", "i") => "eu",
+        ("s", "i") => "seu",
+        ("", "e") => "ngo",
+        ("", "ai") => "ngai",
+        ("", "ao") => "ngao",
+        ("", "ou") => "ngeou",
+        ("", "an") => "ngan",
+        ("", "en") => "ngen",
+        ("", "ang") => "ngang",
+        ("", "i") => "yi",
+        ("", "io") => "yo",
+        ("", "m") => "m",
+        ("", "n") => "n",
+        ("", "ng") => "ng",
+        ("", "ia") => "ya",
+        ("", "ie") => "ye",
+        ("", "iao") => "yao",
+        ("", "iu") => "yeou",
+        ("", "ian") => "yen",
+        ("", "in") => "yin",
+        ("", "iang") => "yang",
+        ("", "ing") => "ying",
+        ("", "iong") => "yong",
+        ("", "u") => "wou",
+        ("", "ua") => "wa",
+        ("", "uo") => "wo",
+        ("", "uai") => "wai",
+        ("", "ui") => "wei",
+        ("", "uan") => "wan",
+        ("", "un") => "wen",
+        ("", "uang") => "wang",
+        ("", "ueng") => "wong",
+        ("", "v") => "yu",
+        ("", "ve") => "yue",
+        ("", "van") => "yuan",
+        ("", "vn") => "yun",
+        ("g" | "k" | "h", "e") => "o",
+        ("b" | "p" | "m" | "f", "eng") => "ong",
+        (_, "a") => "a",
+        (_, "o") => "o",
+        (_, "e") => "ö",
+        (_, "ai") => "ai",
+        (_, "ei") => "ei",
+        (_, "ao") => "ao",
+        (_, "ou") => "eou",
+        (_, "an") => "an",
+        (_, "en") => "en",
+        (_, "ang") => "ang",
+        (_, "eng") => "eng",
+        (_, "ong") => "ong",
+        (_, "er") => "eul",
+        (_, "i") => "i",
+        (_, "ia") => "ia",
+        (_, "ie") => "ie",
+        (_, "iao") => "iao",
+        (_, "iu") => "ieou",
+        (_, "ian") => "ien",
+        (_, "in") => "in",
+        (_, "iang") => "iang",
+        (_, "ing") => "ing",
+        (_, "iong") => "iong",
+        (_, "u") => "ou",
+        (_, "ua") => "oua",
+        ("d" | "t" | "n" | "l" | "z" | "c" | "s" | "r", "uo") => "o",
+        (_, "uo") => "ouo",
+        (_, "uai") => "ouai",
+        (_, "ui") => "ouei",
+        (_, "uan") => "ouan",
+        (_, "un") => "ouen",
+        (_, "uang") => "ouang",
+        (_, "v") => "iu",
+        (_, "ve") => "iue",
+        (_, "van") => "iuan",
+        (_, "vn") => "iun",
+        _ => unreachable!(),
+    }
+}
+
+/// Encode EFEO
+///
+/// EFEO has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_efeo("chong2"), Some("tch'ong".to_owned()));
+/// assert_eq!(encode_efeo("ze2"), Some("tsö".to_owned()));
+///
+/// assert_eq!(encode_efeo("ze"), None);
+/// ```
+pub fn encode_efeo<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, _) = lookup_syllable(s.as_ref())?;
+
+    let mut ret = String::with_capacity(12);
+    ret.push_str(initial(consonant));
+    ret.push_str(rhyme(consonant, final_));
+    Some(ret)
+}
+
+/// Decode EFEO
+///
+/// EFEO has no tones, so the result always has the 5th tone. Typographic
+/// apostrophes are read as `'`. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_efeo("K'ing"), Some("qing5".to_owned()));
+/// assert_eq!(decode_efeo("tsö"), Some("ze5".to_owned()));
+/// assert_eq!(decode_efeo("ts'ing"), Some("qing5".to_owned()));
+/// ```
+pub fn decode_efeo<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let s = normalize(s.as_ref());
+    efeo_table()
+        .iter()
+        .find(|(efeo, _)| *efeo == s)
+        .map(|(_, syllable)| format!("{}5", syllable))
+}
+
+/// Encode a name as EFEO
+///
+/// The syllables are given as numbered pinyin, joined by hyphens and
+/// capitalized, as EFEO writes given names and place names.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_efeo_word(["ze2", "dong1"]), Some("Tsö-tong".to_owned()));
+/// assert_eq!(encode_efeo_word(["chong2", "qing4"]), Some("Tch'ong-k'ing".to_owned()));
+/// ```
+pub fn encode_efeo_word<I, S>(syllables: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for syllable in syllables {
+        let syllable = encode_efeo(syllable)?;
+        if ret.is_empty() {
+            let mut chars = syllable.chars();
+            ret.extend(chars.next()?.to_uppercase());
+            ret.push_str(chars.as_str());
+        } else {
+            ret.push('-');
+            ret.push_str(&syllable);
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Decode an EFEO name to numbered pinyin syllables
+///
+/// Syllables may be separated by hyphens or whitespace, so a full name such as
+/// `Mao Tsö-tong` can be decoded at once. Every syllable has the 5th tone.
+/// Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     decode_efeo_word("Mao Tsö-tong"),
+///     Some(vec!["mao5".to_owned(), "ze5".to_owned(), "dong5".to_owned()])
+/// );
+/// ```
+pub fn decode_efeo_word<S>(s: S) -> Option<Vec<String>>
+where
+    S: AsRef<str>,
+{
+    let ret: Vec<String> = s
+        .as_ref()
+        .split(|c: char| c == '-' || c.is_whitespace())
+        .filter(|syllable| !syllable.is_empty())
+        .map(decode_efeo)
+        .collect::<Option<_>>()?;
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Convert pinyin to EFEO
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_efeo("běi"), Some("pei".to_owned()));
+/// ```
+pub fn pinyin_to_efeo<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_efeo(decode_pinyin(s)?)
+}
+
+/// Convert zhuyin to EFEO
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_efeo("ㄐㄧㄥ"), Some("king".to_owned()));
+/// ```
+pub fn zhuyin_to_efeo<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_efeo(decode_zhuyin(s)?)
+}
+
+/// Convert EFEO to pinyin
+///
+/// EFEO has no tones, so the pinyin has no tone marks.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(efeo_to_pinyin("tchong"), Some("zhong".to_owned()));
+/// ```
+pub fn efeo_to_pinyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_pinyin(decode_efeo(s)?)
+}
+
+/// Lowercase, and replace typographic apostrophes with `'`
+fn normalize(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '’' | '‘' | 'ʼ' | 'ʻ' => '\'',
+            c => c,
+        })
+        .collect()
+}
+
+/// Every syllable in EFEO, paired with its numbered pinyin spelling
+///
+/// `j`, `q` and `x` syllables are listed a second time with `ts`, `ts'` and `s`.
+/// `lo` is left out, since it's spelled like `luo` and `lo` is read as `luo`.
+fn efeo_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut ret = Vec::with_capacity(SYLLABLES.len() * 2);
+        for (syllable, (consonant, final_)) in SYLLABLES.entries() {
+            if *syllable == "lo" {
+                continue;
+            }
+            let rhyme = rhyme(consonant, final_);
+            ret.push((format!("{}{}", initial(consonant), rhyme), *syllable));
+
+            let dental = match *consonant {
+                "j" => "ts",
+                "q" => "ts'",
+                "x" => "s",
+                _ => continue,
+            };
+            ret.push((format!("{}{}", dental, rhyme), *syllable));
+        }
+        ret
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_efeo_test() {
+        assert_eq!(encode_efeo("bei3"), s!("pei"));
+        assert_eq!(encode_efeo("jing1"), s!("king"));
+        assert_eq!(encode_efeo("qing4"), s!("k'ing"));
+        assert_eq!(encode_efeo("xia4"), s!("hia"));
+        assert_eq!(encode_efeo("mao2"), s!("mao"));
+        assert_eq!(encode_efeo("dong1"), s!("tong"));
+        assert_eq!(encode_efeo("zhong1"), s!("tchong"));
+        assert_eq!(encode_efeo("shan1"), s!("chan"));
+        assert_eq!(encode_efeo("ren2"), s!("jen"));
+        assert_eq!(encode_efeo("ge1"), s!("ko"));
+        assert_eq!(encode_efeo("he2"), s!("ho"));
+        assert_eq!(encode_efeo("de2"), s!("tö"));
+        assert_eq!(encode_efeo("meng4"), s!("mong"));
+        assert_eq!(encode_efeo("zhou1"), s!("tcheou"));
+        assert_eq!(encode_efeo("liu2"), s!("lieou"));
+        assert_eq!(encode_efeo("tian1"), s!("t'ien"));
+        // 国 kouo and 说 chouo, but 多 to, 罗 lo, 作 tso, 所 so and 若 jo
+        assert_eq!(encode_efeo("guo2"), s!("kouo"));
+        assert_eq!(encode_efeo("shuo1"), s!("chouo"));
+        assert_eq!(encode_efeo("duo1"), s!("to"));
+        assert_eq!(encode_efeo("luo2"), s!("lo"));
+        assert_eq!(encode_efeo("zuo4"), s!("tso"));
+        assert_eq!(encode_efeo("suo3"), s!("so"));
+        assert_eq!(encode_efeo("ruo4"), s!("jo"));
+        // 翁
+        assert_eq!(encode_efeo("weng1"), s!("wong"));
+        assert_eq!(encode_efeo("gui4"), s!("kouei"));
+        assert_eq!(encode_efeo("lv4"), s!("liu"));
+        assert_eq!(encode_efeo("xue2"), s!("hiue"));
+        assert_eq!(encode_efeo("er4"), s!("eul"));
+        assert_eq!(encode_efeo("an1"), s!("ngan"));
+        assert_eq!(encode_efeo("you3"), s!("yeou"));
+        assert_eq!(encode_efeo("wu3"), s!("wou"));
+        assert_eq!(encode_efeo("yuan2"), s!("yuan"));
+        // Apical vowels
+        assert_eq!(encode_efeo("zhi1"), s!("tche"));
+        assert_eq!(encode_efeo("ri4"), s!("je"));
+        assert_eq!(encode_efeo("zi3"), s!("tseu"));
+        assert_eq!(encode_efeo("ci2"), s!("ts'eu"));
+        assert_eq!(encode_efeo("si1"), s!("sseu"));
+
+        assert_eq!(encode_efeo("bei"), None);
+        assert_eq!(encode_efeo("r5"), None);
+        assert_eq!(encode_efeo(""), None);
+    }
+
+    #[test]
+    fn decode_efeo_test() {
+        assert_eq!(decode_efeo("pei"), s!("bei5"));
+        assert_eq!(decode_efeo("king"), s!("jing5"));
+        assert_eq!(decode_efeo("Kouo"), s!("guo5"));
+        assert_eq!(decode_efeo("tch’ong"), s!("chong5"));
+        assert_eq!(decode_efeo("sseu"), s!("si5"));
+        assert_eq!(decode_efeo("liu"), s!("lv5"));
+        assert_eq!(decode_efeo("yuan"), s!("yvan5"));
+        assert_eq!(decode_efeo("ts'ing"), s!("qing5"));
+        assert_eq!(decode_efeo("si"), s!("xi5"));
+        assert_eq!(decode_efeo("to"), s!("duo5"));
+        assert_eq!(decode_efeo("lo"), s!("luo5"));
+        assert_eq!(decode_efeo("so"), s!("suo5"));
+        assert_eq!(decode_efeo("wong"), s!("weng5"));
+
+        assert_eq!(decode_efeo("tchong-king"), None);
+        assert_eq!(decode_efeo("zhong"), None);
+        assert_eq!(decode_efeo(""), None);
+
+        // Every spelling belongs to one syllable
+        for (efeo, syllable) in efeo_table() {
+            assert_eq!(decode_efeo(efeo), Some(format!("{}5", syllable)));
+        }
+    }
+
+    #[test]
+    fn efeo_word_test() {
+        assert_eq!(encode_efeo_word(["mao2"]), s!("Mao"));
+        assert_eq!(encode_efeo_word(["bei3", "jing1"]), s!("Pei-king"));
+        assert_eq!(encode_efeo_word(["er4", "lang2"]), s!("Eul-lang"));
+        assert_eq!(encode_efeo_word(["bei3", "jing"]), None);
+        assert_eq!(encode_efeo_word(Vec::<&str>::new()), None);
+
+        assert_eq!(
+            decode_efeo_word("Tch'ong-k'ing"),
+            Some(vec!["chong5".to_owned(), "qing5".to_owned()])
+        );
+        assert_eq!(
+            decode_efeo_word(" Sseu-ma  Ts'ien "),
+            Some(vec!["si5".to_owned(), "ma5".to_owned(), "qian5".to_owned()])
+        );
+        assert_eq!(decode_efeo_word("Pékin"), None);
+        assert_eq!(decode_efeo_word("-"), None);
+    }
+
+    #[test]
+    fn efeo_conversion_test() {
+        assert_eq!(pinyin_to_efeo("zhōu"), s!("tcheou"));
+        assert_eq!(zhuyin_to_efeo("ㄒㄩㄥˊ"), s!("hiong"));
+        assert_eq!(efeo_to_pinyin("Mao"), s!("mao"));
+
+        assert_eq!(pinyin_to_efeo("啊"), None);
+        assert_eq!(zhuyin_to_efeo("ㄩㄝㄝ"), None);
+        assert_eq!(efeo_to_pinyin("mau"), None);
+    }
+}
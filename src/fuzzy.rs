@@ -0,0 +1,241 @@
+//! Fuzzy matching of syllables.
+//!
+//! Speakers from the south of China and from Taiwan often merge some initials
+//! and finals. Each rule of [`FuzzyRules`] makes one pair of them equivalent,
+//! both in pinyin and in zhuyin, since the rules work on the initial and final
+//! of the syllable table.
+use crate::{Notation, Syllable, Zhuyin, SYLLABLES};
+
+/// Which initials and finals count as the same
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FuzzyRules {
+    /// `zh` and `z` (ㄓ and ㄗ)
+    pub zh_z: bool,
+    /// `ch` and `c` (ㄔ and ㄘ)
+    pub ch_c: bool,
+    /// `sh` and `s` (ㄕ and ㄙ)
+    pub sh_s: bool,
+    /// `n` and `l` (ㄋ and ㄌ)
+    pub n_l: bool,
+    /// `f` and `h` (ㄈ and ㄏ)
+    pub f_h: bool,
+    /// `an` and `ang` (ㄢ and ㄤ), with `ian`/`iang` and `uan`/`uang`
+    pub an_ang: bool,
+    /// `en` and `eng` (ㄣ and ㄥ), with `wen`/`weng`
+    pub en_eng: bool,
+    /// `in` and `ing` (ㄧㄣ and ㄧㄥ)
+    pub in_ing: bool,
+}
+
+impl FuzzyRules {
+    /// Every rule enabled
+    pub fn all() -> FuzzyRules {
+        FuzzyRules {
+            zh_z: true,
+            ch_c: true,
+            sh_s: true,
+            n_l: true,
+            f_h: true,
+            an_ang: true,
+            en_eng: true,
+            in_ing: true,
+        }
+    }
+
+    /// Returns the initial every initial equivalent to this one maps to
+    fn initial(&self, initial: &'static str) -> &'static str {
+        match initial {
+            "zh" if self.zh_z => "z",
+            "ch" if self.ch_c => "c",
+            "sh" if self.sh_s => "s",
+            "l" if self.n_l => "n",
+            "h" if self.f_h => "f",
+            _ => initial,
+        }
+    }
+
+    /// Returns the final every final equivalent to this one maps to
+    fn final_(&self, final_: &'static str) -> &'static str {
+        match final_ {
+            "ang" if self.an_ang => "an",
+            "iang" if self.an_ang => "ian",
+            "uang" if self.an_ang => "uan",
+            "eng" if self.en_eng => "en",
+            "ueng" if self.en_eng => "un",
+            "ing" if self.in_ing => "in",
+            _ => final_,
+        }
+    }
+
+    /// Returns every valid syllable equivalent to this one
+    ///
+    /// The syllable itself comes first, then the others in the order of the
+    /// syllable table. The tone is kept. Returns an empty list if the syllable
+    /// isn't valid.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let rules = FuzzyRules { zh_z: true, en_eng: true, ..Default::default() };
+    /// let syllables: Vec<String> = rules
+    ///     .equivalents(&Syllable::new("zhen1").unwrap())
+    ///     .iter()
+    ///     .map(Syllable::to_string)
+    ///     .collect();
+    /// assert_eq!(syllables, ["zhen1", "zheng1", "zen1", "zeng1"]);
+    /// ```
+    pub fn equivalents(&self, syllable: &Syllable) -> Vec<Syllable> {
+        let Some((initial, final_)) = syllable.initial_final() else {
+            return Vec::new();
+        };
+        let key = (self.initial(initial), self.final_(final_));
+
+        let mut ret = vec![syllable.clone()];
+        for (other, (other_initial, other_final)) in SYLLABLES.entries() {
+            let other_key = (self.initial(other_initial), self.final_(other_final));
+            if other_key != key || (*other_initial, *other_final) == (initial, final_) {
+                continue;
+            }
+            if let Some(other) = Syllable::with_tone(other, syllable.tone) {
+                ret.push(other);
+            }
+        }
+        ret
+    }
+
+    /// Returns true if two syllables are the same under these rules
+    ///
+    /// The tones must be the same. Invalid syllables only match themselves.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let rules = FuzzyRules { in_ing: true, ..Default::default() };
+    /// let a = Zhuyin.parse("ㄒㄧㄣ").unwrap();
+    /// let b = Zhuyin.parse("ㄒㄧㄥ").unwrap();
+    /// assert!(rules.matches(&a, &b));
+    /// assert!(!FuzzyRules::default().matches(&a, &b));
+    /// ```
+    pub fn matches(&self, a: &Syllable, b: &Syllable) -> bool {
+        if a.tone != b.tone {
+            return false;
+        }
+        match (a.initial_final(), b.initial_final()) {
+            (Some((a_initial, a_final)), Some((b_initial, b_final))) => {
+                self.initial(a_initial) == self.initial(b_initial)
+                    && self.final_(a_final) == self.final_(b_final)
+            }
+            _ => a == b,
+        }
+    }
+}
+
+/// Returns every syllable equivalent to some numbered pinyin, itself first
+///
+/// Returns an empty list on a missing tone or invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let rules = FuzzyRules { n_l: true, ..Default::default() };
+/// assert_eq!(fuzzy_syllables("nv3", rules), ["nv3", "lv3"]);
+/// assert_eq!(fuzzy_syllables("nin2", rules), ["nin2", "lin2"]);
+/// assert_eq!(fuzzy_syllables("lia3", rules), ["lia3"]);
+/// ```
+pub fn fuzzy_syllables<S>(s: S, rules: FuzzyRules) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    Syllable::new(s.as_ref())
+        .map(|syllable| rules.equivalents(&syllable))
+        .unwrap_or_default()
+        .iter()
+        .map(Syllable::to_string)
+        .collect()
+}
+
+/// Returns every syllable equivalent to some zhuyin, itself first
+///
+/// Returns an empty list if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let rules = FuzzyRules { sh_s: true, en_eng: true, ..Default::default() };
+/// assert_eq!(fuzzy_zhuyin("ㄕㄣˊ", rules), ["ㄕㄣˊ", "ㄕㄥˊ", "ㄙㄣˊ", "ㄙㄥˊ"]);
+/// ```
+pub fn fuzzy_zhuyin<S>(s: S, rules: FuzzyRules) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    Zhuyin
+        .parse(s.as_ref())
+        .map(|syllable| rules.equivalents(&syllable))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|syllable| Zhuyin.render(syllable))
+        .collect()
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn equivalents_test() {
+        let all = FuzzyRules::all();
+        assert_eq!(
+            fuzzy_syllables("zhang1", all),
+            ["zhang1", "zhan1", "zan1", "zang1"]
+        );
+        assert_eq!(fuzzy_syllables("fei1", all), ["fei1", "hei1"]);
+        assert_eq!(fuzzy_syllables("huang2", all), ["huang2", "huan2"]);
+        assert_eq!(fuzzy_syllables("wen2", all), ["wen2", "weng2"]);
+        assert_eq!(fuzzy_syllables("yin1", all), ["yin1", "ying1"]);
+        assert_eq!(
+            fuzzy_syllables("liang2", all),
+            ["liang2", "nian2", "niang2", "lian2"]
+        );
+        assert_eq!(fuzzy_syllables("ma3", all), ["ma3"]);
+        assert_eq!(fuzzy_syllables("zhang1", FuzzyRules::default()), ["zhang1"]);
+
+        let rules = FuzzyRules {
+            ch_c: true,
+            ..Default::default()
+        };
+        assert_eq!(fuzzy_syllables("chuang4", rules), ["chuang4"]);
+        assert_eq!(fuzzy_syllables("chi1", rules), ["chi1", "ci1"]);
+
+        assert_eq!(
+            fuzzy_zhuyin("ㄌㄧㄣˊ", all),
+            ["ㄌㄧㄣˊ", "ㄋㄧㄣˊ", "ㄋㄧㄥˊ", "ㄌㄧㄥˊ"]
+        );
+        assert_eq!(fuzzy_zhuyin("ㄓ", all), ["ㄓ", "ㄗ"]);
+        assert_eq!(fuzzy_zhuyin("ㄜ˙", all), ["ㄜ˙"]);
+        assert_eq!(fuzzy_zhuyin("ㄝ", all), ["ㄝ"]);
+
+        assert!(fuzzy_syllables("zhang", all).is_empty());
+        assert!(fuzzy_syllables("r5", all).is_empty());
+        assert!(fuzzy_zhuyin("", all).is_empty());
+    }
+
+    #[test]
+    fn matches_test() {
+        let syllable = |s| Syllable::new(s).unwrap();
+        let all = FuzzyRules::all();
+        assert!(all.matches(&syllable("zhang1"), &syllable("zan1")));
+        assert!(all.matches(&syllable("nv3"), &syllable("lv3")));
+        assert!(all.matches(&syllable("wen1"), &syllable("weng1")));
+        assert!(!all.matches(&syllable("zhang1"), &syllable("zhang2")));
+        assert!(!all.matches(&syllable("zhang1"), &syllable("chang1")));
+        assert!(!all.matches(&syllable("jin1"), &syllable("jvn1")));
+        assert!(all.matches(&syllable("r5"), &syllable("r5")));
+
+        let rules = FuzzyRules {
+            an_ang: true,
+            ..Default::default()
+        };
+        assert!(rules.matches(&syllable("chuan2"), &syllable("chuang2")));
+        assert!(!rules.matches(&syllable("chuan2"), &syllable("cuan2")));
+    }
+}
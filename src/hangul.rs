@@ -0,0 +1,285 @@
+//! Korean Hangul transcription.
+//!
+//! Follows the National Institute of Korean Language table for Mandarin: each
+//! final is written as Hangul with a silent `ㅇ`, which the initial then
+//! replaces. After `ㅈ`, `ㅉ` and `ㅊ` the vowels `ㅑ`, `ㅖ`, `ㅛ` and `ㅠ` are
+//! written `ㅏ`, `ㅔ`, `ㅗ` and `ㅜ`.
+use crate::{decode_pinyin, decode_zhuyin, lookup_syllable, Notation, Syllable, SYLLABLES};
+
+const HANGUL_BASE: u32 = 0xAC00;
+const VOWEL_COUNT: u32 = 21;
+const TAIL_COUNT: u32 = 28;
+
+// Indices of the leading consonants and the vowels used below
+const L_SILENT: u32 = 11;
+const L_J: u32 = 12;
+const L_JJ: u32 = 13;
+const L_CH: u32 = 14;
+const V_A: u32 = 0;
+const V_YA: u32 = 2;
+const V_E: u32 = 5;
+const V_YE: u32 = 7;
+const V_O: u32 = 8;
+const V_YO: u32 = 12;
+const V_U: u32 = 13;
+const V_YU: u32 = 17;
+
+/// Hangul, such as `장`
+///
+/// Parsing gives the first candidate of `decode_hangul`, with the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Hangul;
+
+impl Notation for Hangul {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(decode_hangul(s).first()?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_hangul(syllable.to_string())
+    }
+}
+
+/// Returns the Hangul leading consonant index of an initial
+fn initial(initial: &str) -> u32 {
+    match initial {
+        "g" => 0,
+        "n" => 2,
+        "d" => 3,
+        "l" | "r" => 5,
+        "m" => 6,
+        "b" => 7,
+        "x" | "sh" => 9,
+        "s" => 10,
+        "j" | "zh" => L_J,
+        "z" => L_JJ,
+        "q" | "ch" | "c" => L_CH,
+        "k" => 15,
+        "t" => 16,
+        "p" | "f" => 17,
+        "h" => 18,
+        _ => L_SILENT,
+    }
+}
+
+/// Returns the Hangul spelling of a final, written with a silent initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("zh" | "ch" | "sh" | "r" | "z" | "c" | "s", "i") => "으",
+        ("", "ui") => "웨이",
+        ("", "un") => "원",
+        ("", "io") => "요",
+        ("", "m") => "음",
+        ("", "n") => "은",
+        ("", "ng") => "응",
+        (_, "ui") => "우이",
+        (_, "un") => "운",
+        (_, "a") => "아",
+        (_, "o") => "오",
+        (_, "e") => "어",
+        (_, "ai") => "아이",
+        (_, "ei") => "에이",
+        (_, "ao") => "아오",
+        (_, "ou") => "어우",
+        (_, "an") => "안",
+        (_, "en") => "언",
+        (_, "ang") => "앙",
+        (_, "eng") => "엉",
+        (_, "ong") => "웅",
+        (_, "er") => "얼",
+        (_, "i") => "이",
+        (_, "ia") => "야",
+        (_, "ie") => "예",
+        (_, "iao") => "야오",
+        (_, "iu") => "유",
+        (_, "ian") => "옌",
+        (_, "in") => "인",
+        (_, "iang") => "양",
+        (_, "ing") => "잉",
+        (_, "iong") => "융",
+        (_, "u") => "우",
+        (_, "ua") => "와",
+        (_, "uo") => "워",
+        (_, "uai") => "와이",
+        (_, "uan") => "완",
+        (_, "uang") => "왕",
+        (_, "ueng") => "웡",
+        (_, "v") => "위",
+        (_, "ve") => "웨",
+        (_, "van") => "위안",
+        (_, "vn") => "윈",
+        _ => unreachable!(),
+    }
+}
+
+/// Put the initial in the first block of a final
+fn compose(consonant: &str, final_: &str) -> String {
+    let rhyme = rhyme(consonant, final_);
+    let lead = initial(consonant);
+
+    let mut chars = rhyme.chars();
+    let first = chars.next().unwrap() as u32 - HANGUL_BASE;
+    let tail = first % TAIL_COUNT;
+    let mut vowel = first / TAIL_COUNT % VOWEL_COUNT;
+
+    if lead == L_J || lead == L_JJ || lead == L_CH {
+        vowel = match vowel {
+            V_YA => V_A,
+            V_YE => V_E,
+            V_YO => V_O,
+            V_YU => V_U,
+            _ => vowel,
+        };
+    }
+
+    let block = HANGUL_BASE + (lead * VOWEL_COUNT + vowel) * TAIL_COUNT + tail;
+    let mut ret = String::with_capacity(rhyme.len());
+    ret.push(char::from_u32(block).unwrap());
+    ret.extend(chars);
+    ret
+}
+
+/// Encode Hangul
+///
+/// Hangul transcription has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_hangul("bei3"), Some("베이".to_owned()));
+/// assert_eq!(encode_hangul("jing1"), Some("징".to_owned()));
+///
+/// assert_eq!(encode_hangul("jing"), None);
+/// ```
+pub fn encode_hangul<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, _) = lookup_syllable(s.as_ref())?;
+    Some(compose(consonant, final_))
+}
+
+/// Decode Hangul
+///
+/// Several syllables can share a Hangul spelling, so every candidate is returned,
+/// always with the 5th tone. Returns an empty list if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_hangul("마오"), vec!["mao5".to_owned()]);
+/// assert_eq!(decode_hangul("주"), vec!["jiu5".to_owned(), "zhu5".to_owned()]);
+/// ```
+pub fn decode_hangul<S>(s: S) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref();
+    SYLLABLES
+        .entries()
+        .filter(|(_, (consonant, final_))| compose(consonant, final_) == s)
+        .map(|(syllable, _)| format!("{}5", syllable))
+        .collect()
+}
+
+/// Convert pinyin to Hangul
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_hangul("zhōu"), Some("저우".to_owned()));
+/// ```
+pub fn pinyin_to_hangul<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_hangul(decode_pinyin(s)?)
+}
+
+/// Convert zhuyin to Hangul
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_hangul("ㄊㄧㄢ"), Some("톈".to_owned()));
+/// ```
+pub fn zhuyin_to_hangul<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_hangul(decode_zhuyin(s)?)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_hangul_test() {
+        assert_eq!(encode_hangul("mao2"), s!("마오"));
+        assert_eq!(encode_hangul("ze2"), s!("쩌"));
+        assert_eq!(encode_hangul("dong1"), s!("둥"));
+        assert_eq!(encode_hangul("tian1"), s!("톈"));
+        assert_eq!(encode_hangul("jin1"), s!("진"));
+        assert_eq!(encode_hangul("hang2"), s!("항"));
+        assert_eq!(encode_hangul("zhou1"), s!("저우"));
+        assert_eq!(encode_hangul("shen1"), s!("선"));
+        assert_eq!(encode_hangul("zhen4"), s!("전"));
+        assert_eq!(encode_hangul("hui1"), s!("후이"));
+        assert_eq!(encode_hangul("wei4"), s!("웨이"));
+        assert_eq!(encode_hangul("kun1"), s!("쿤"));
+        assert_eq!(encode_hangul("wen2"), s!("원"));
+        assert_eq!(encode_hangul("guo2"), s!("궈"));
+        assert_eq!(encode_hangul("liu2"), s!("류"));
+        assert_eq!(encode_hangul("lve4"), s!("뤠"));
+        assert_eq!(encode_hangul("xuan2"), s!("쉬안"));
+        assert_eq!(encode_hangul("yuan2"), s!("위안"));
+        assert_eq!(encode_hangul("er4"), s!("얼"));
+        // Apical vowels
+        assert_eq!(encode_hangul("zhi1"), s!("즈"));
+        assert_eq!(encode_hangul("si1"), s!("쓰"));
+        assert_eq!(encode_hangul("ri4"), s!("르"));
+        // ㅈ, ㅉ, ㅊ drop the y-glide
+        assert_eq!(encode_hangul("jia1"), s!("자"));
+        assert_eq!(encode_hangul("jie1"), s!("제"));
+        assert_eq!(encode_hangul("qiao2"), s!("차오"));
+        assert_eq!(encode_hangul("jiu3"), s!("주"));
+        assert_eq!(encode_hangul("jiong3"), s!("중"));
+        assert_eq!(encode_hangul("xiang1"), s!("샹"));
+        assert_eq!(encode_hangul("xiong2"), s!("슝"));
+
+        assert_eq!(encode_hangul("mao"), None);
+        assert_eq!(encode_hangul("啊"), None);
+        assert_eq!(encode_hangul(""), None);
+    }
+
+    #[test]
+    fn decode_hangul_test() {
+        assert_eq!(decode_hangul("베이"), vec!["bei5"]);
+        assert_eq!(decode_hangul("쩌"), vec!["ze5"]);
+        assert_eq!(decode_hangul("위안"), vec!["yvan5"]);
+        assert_eq!(decode_hangul("펑"), vec!["peng5", "feng5"]);
+        assert_eq!(decode_hangul("주"), vec!["jiu5", "zhu5"]);
+
+        assert!(decode_hangul("베").is_empty());
+        assert!(decode_hangul("bei").is_empty());
+        assert!(decode_hangul("").is_empty());
+    }
+
+    #[test]
+    fn hangul_conversion_test() {
+        assert_eq!(pinyin_to_hangul("běi"), s!("베이"));
+        assert_eq!(pinyin_to_hangul("lǚ"), s!("뤼"));
+        assert_eq!(zhuyin_to_hangul("ㄐㄧㄥ"), s!("징"));
+        assert_eq!(zhuyin_to_hangul("ㄕˋ"), s!("스"));
+
+        assert_eq!(pinyin_to_hangul("啊"), None);
+        assert_eq!(zhuyin_to_hangul("ㄩㄝㄝ"), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Built-in readings of common characters, with the `hanzi` feature.
+//!
+//! The table is built into the crate from `data/hanzi.txt`, which covers
+//! level 1 of 通用规范汉字表, so simple uses need no data files. It has one reading per character, the one the character
+//! has most often alone; load a dictionary to read words.
+use crate::{encode_pinyin, encode_zhuyin, Lexicon, Syllable};
+
+// The HANZI static map
+include!(concat!(env!("OUT_DIR"), "/hanzi.rs"));
+
+/// Returns the most common reading of a character as numbered pinyin
+///
+/// Returns None if the character isn't in the built-in table.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(hanzi_reading('中'), Some("zhong1"));
+/// assert_eq!(hanzi_reading('绿'), Some("lv4"));
+/// assert_eq!(hanzi_reading('a'), None);
+/// ```
+pub fn hanzi_reading(c: char) -> Option<&'static str> {
+    HANZI.get(c.encode_utf8(&mut [0; 4])).copied()
+}
+
+/// Returns the most common reading of a character as pinyin
+///
+/// Returns None if the character isn't in the built-in table.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(hanzi_to_pinyin('中'), Some("zhōng".to_owned()));
+/// ```
+pub fn hanzi_to_pinyin(c: char) -> Option<String> {
+    encode_pinyin(hanzi_reading(c)?)
+}
+
+/// Returns the most common reading of a character as zhuyin
+///
+/// Returns None if the character isn't in the built-in table.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(hanzi_to_zhuyin('中'), Some("ㄓㄨㄥ".to_owned()));
+/// ```
+pub fn hanzi_to_zhuyin(c: char) -> Option<String> {
+    encode_zhuyin(hanzi_reading(c)?)
+}
+
+/// The built-in table as a lexicon of single characters
+///
+/// Layer a dictionary over it with [`Layered`](crate::Layered) to read the
+/// words the dictionary knows and fall back to single characters.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(annotate_text("你好，世界", &CommonChars, &Pinyin), "nǐ hǎo，shì jiè");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CommonChars;
+
+impl Lexicon for CommonChars {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        HANZI
+            .get(word)
+            .and_then(|reading| Syllable::new(reading))
+            .map(|syllable| vec![vec![syllable]])
+            .unwrap_or_default()
+    }
+
+    fn longest_word(&self) -> usize {
+        1
+    }
+
+    fn words(&self) -> Vec<&str> {
+        HANZI.keys().copied().collect()
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn hanzi_test() {
+        for (c, reading) in HANZI.entries() {
+            let syllable = Syllable::new(reading);
+            assert!(syllable.is_some_and(|s| s.is_valid()), "{} {}", c, reading);
+        }
+
+        // Every level 1 character, and only those
+        let level1: String = include_str!("../data/level1.txt")
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        for c in level1.chars() {
+            assert!(hanzi_reading(c).is_some(), "{}", c);
+        }
+        assert_eq!(HANZI.len(), level1.chars().count());
+        assert_eq!(hanzi_reading('於'), None);
+
+        assert_eq!(hanzi_to_pinyin('哟'), Some("yō".to_owned()));
+        assert_eq!(hanzi_reading('扛'), Some("kang2"));
+        assert_eq!(hanzi_reading('铝'), Some("lv3"));
+        assert_eq!(hanzi_reading('筷'), Some("kuai4"));
+        assert_eq!(hanzi_reading('蚊'), Some("wen2"));
+        assert_eq!(hanzi_reading('饺'), Some("jiao3"));
+        assert_eq!(hanzi_reading('晾'), Some("liang4"));
+
+        assert_eq!(hanzi_reading('的'), Some("de5"));
+        assert_eq!(hanzi_reading('学'), Some("xve2"));
+        assert_eq!(hanzi_reading('行'), Some("xing2"));
+        assert_eq!(hanzi_to_pinyin('学'), Some("xué".to_owned()));
+        assert_eq!(hanzi_to_pinyin('吗'), Some("ma".to_owned()));
+        assert_eq!(hanzi_to_zhuyin('吗'), Some("ㄇㄚ˙".to_owned()));
+        assert_eq!(hanzi_to_zhuyin('女'), Some("ㄋㄩˇ".to_owned()));
+        assert_eq!(hanzi_reading('。'), None);
+        assert_eq!(hanzi_to_pinyin('𠀀'), None);
+
+        assert_eq!(CommonChars.readings("中国").len(), 0);
+        assert_eq!(CommonChars.readings("").len(), 0);
+        assert_eq!(CommonChars.words().len(), HANZI.len());
+        let cedict = crate::Cedict::parse("銀行 银行 [yin2 hang2] /bank/\n");
+        let layered = crate::Layered::new(&cedict, CommonChars);
+        assert_eq!(
+            crate::annotate_text("我去银行", &layered, &crate::Pinyin),
+            "wǒ qù yínháng"
+        );
+        assert_eq!(
+            crate::annotate_text("我去银行", &CommonChars, &crate::Pinyin),
+            "wǒ qù yín xíng"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Tables for ibus-table and fcitx5 table input methods.
+//!
+//! Both are plain text lists of key sequences and what they type. Every
+//! syllable in every tone is one entry, typed either as numbered pinyin or on
+//! a zhuyin keyboard layout, and written in any notation.
+use std::fmt::Write;
+
+use crate::{
+    encode_zhuyin, typed_syllable, zhuyin_to_keys, KeyboardLayout, NotationKind, Syllable,
+    SYLLABLES,
+};
+
+/// How the syllables of a table are typed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableKeys {
+    /// Numbered pinyin, such as `zhang1`
+    #[default]
+    Pinyin,
+    /// Zhuyin on a keyboard layout, leaving out the space of the first tone
+    Zhuyin(KeyboardLayout),
+}
+
+/// Returns the keys and text of every entry, and the keys used
+fn entries(keys: TableKeys, output: NotationKind) -> (Vec<(String, String)>, String) {
+    let mut entries = Vec::with_capacity(SYLLABLES.len() * 5);
+    for syllable in SYLLABLES.keys() {
+        for tone in 1..=5 {
+            let numbered = format!("{}{}", syllable, tone);
+            let typed = match keys {
+                TableKeys::Pinyin => Some(format!("{}{}", typed_syllable(syllable), tone)),
+                TableKeys::Zhuyin(layout) => encode_zhuyin(&numbered)
+                    .and_then(|zhuyin| zhuyin_to_keys(zhuyin, layout))
+                    .map(|keys| keys.trim_end().to_owned()),
+            };
+            let text =
+                Syllable::new(&numbered).and_then(|syllable| output.notation().render(&syllable));
+            if let (Some(typed), Some(text)) = (typed, text) {
+                entries.push((typed, text));
+            }
+        }
+    }
+
+    let mut chars: Vec<char> = entries.iter().flat_map(|(keys, _)| keys.chars()).collect();
+    chars.sort_unstable();
+    chars.dedup();
+    (entries, chars.into_iter().collect())
+}
+
+/// Generate an ibus-table source table, for `ibus-table-createdb`
+///
+/// Returns None if the name is empty or has a line break.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let table = ibus_table(
+///     "Zhuyin",
+///     TableKeys::Zhuyin(KeyboardLayout::Dachen),
+///     NotationKind::Zhuyin,
+/// )
+/// .unwrap();
+/// assert!(table.contains("\nNAME = Zhuyin\n"));
+/// assert!(table.contains("\nsu3\tㄋㄧˇ\t0\n"));
+/// ```
+pub fn ibus_table(name: &str, keys: TableKeys, output: NotationKind) -> Option<String> {
+    if name.trim().is_empty() || name.contains(['\n', '\r']) {
+        return None;
+    }
+
+    let (entries, chars) = entries(keys, output);
+    let max_key_length = entries.iter().map(|(keys, _)| keys.len()).max()?;
+
+    let mut ret = String::new();
+    writeln!(ret, "### File header must not be modified").unwrap();
+    writeln!(ret, "### This file must be encoded into UTF-8.").unwrap();
+    writeln!(
+        ret,
+        "### Generated by pinyin_zhuyin {}",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    writeln!(ret, "BEGIN_DEFINITION").unwrap();
+    writeln!(ret, "NAME = {}", name).unwrap();
+    writeln!(ret, "LANGUAGES = zh").unwrap();
+    writeln!(ret, "LAYOUT = us").unwrap();
+    writeln!(ret, "MAX_KEY_LENGTH = {}", max_key_length).unwrap();
+    writeln!(ret, "VALID_INPUT_CHARS = {}", chars).unwrap();
+    writeln!(ret, "AUTO_COMMIT = FALSE").unwrap();
+    writeln!(ret, "AUTO_SELECT = FALSE").unwrap();
+    writeln!(ret, "USER_CAN_DEFINE_PHRASE = FALSE").unwrap();
+    writeln!(ret, "DYNAMIC_ADJUST = TRUE").unwrap();
+    writeln!(ret, "END_DEFINITION").unwrap();
+    writeln!(ret, "BEGIN_TABLE").unwrap();
+    for (keys, text) in entries {
+        writeln!(ret, "{}\t{}\t0", keys, text).unwrap();
+    }
+    writeln!(ret, "END_TABLE").unwrap();
+    Some(ret)
+}
+
+/// Generate an fcitx5 table source, for `libime_tabledict`
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let table = fcitx5_table(TableKeys::Pinyin, NotationKind::Zhuyin);
+/// assert!(table.starts_with("KeyCode=12345abcdefghijklmnopqrstuvwxyz\nLength=7\n"));
+/// assert!(table.contains("\nzhuang1 ㄓㄨㄤ\n"));
+/// ```
+pub fn fcitx5_table(keys: TableKeys, output: NotationKind) -> String {
+    let (entries, chars) = entries(keys, output);
+    let length = entries
+        .iter()
+        .map(|(keys, _)| keys.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut ret = String::new();
+    writeln!(ret, "KeyCode={}", chars).unwrap();
+    writeln!(ret, "Length={}", length).unwrap();
+    writeln!(ret, "[Data]").unwrap();
+    for (keys, text) in entries {
+        writeln!(ret, "{} {}", keys, text).unwrap();
+    }
+    ret
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn ibus_table_test() {
+        let table = ibus_table("Pinyin", TableKeys::Pinyin, NotationKind::Pinyin).unwrap();
+        assert!(table.starts_with("### File header must not be modified\n"));
+        assert!(table.contains("\nMAX_KEY_LENGTH = 7\n"));
+        assert!(table.contains("\nVALID_INPUT_CHARS = 12345abcdefghijklmnopqrstuvwxyz\n"));
+        assert!(table.contains("\nzhang1\tzhāng\t0\n"));
+        assert!(table.contains("\nlv4\tlǜ\t0\n"));
+        assert!(table.contains("\nju4\tjù\t0\n"));
+        assert!(table.ends_with("\nEND_TABLE\n"));
+        assert_eq!(table.matches("\t0\n").count(), SYLLABLES.len() * 5);
+
+        let table = ibus_table(
+            "注音",
+            TableKeys::Zhuyin(KeyboardLayout::Eten),
+            NotationKind::Pinyin,
+        )
+        .unwrap();
+        assert!(table.contains("\nMAX_KEY_LENGTH = 4\n"));
+        assert!(table.contains("\n,x0\tzhuāng\t0\n"));
+        assert!(table.contains("\n,x04\tzhuàng\t0\n"));
+
+        assert_eq!(
+            ibus_table("", TableKeys::Pinyin, NotationKind::Pinyin),
+            None
+        );
+        assert_eq!(
+            ibus_table("a\nb", TableKeys::Pinyin, NotationKind::Pinyin),
+            None
+        );
+    }
+
+    #[test]
+    fn fcitx5_table_test() {
+        let table = fcitx5_table(
+            TableKeys::Zhuyin(KeyboardLayout::Dachen),
+            NotationKind::Zhuyin,
+        );
+        assert!(table.starts_with("KeyCode=,-./0123456789;abcdefghijklmnopqrstuvwxyz\n"));
+        assert!(table.contains("\nLength=4\n[Data]\n"));
+        assert!(table.contains("\n5;4 ㄓㄤˋ\n"));
+        assert!(table.contains("\n5; ㄓㄤ\n"));
+        assert!(table.contains("\na87 ㄇㄚ˙\n"));
+
+        // Hsu keys are shared, so one key sequence types several syllables
+        let table = fcitx5_table(TableKeys::Zhuyin(KeyboardLayout::Hsu), NotationKind::Zhuyin);
+        assert!(table.contains("\nl ㄦ\n"));
+        assert!(table.contains("\nl ㄥ\n"));
+    }
+}
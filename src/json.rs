@@ -0,0 +1,284 @@
+//! A small JSON parser, for dictionary exports.
+//!
+//! Only what loading data needs: parsing a whole document into a tree. Object
+//! members keep the order of the document.
+
+/// How deep arrays and objects may nest, so parsing can't overflow the stack
+const MAX_DEPTH: usize = 128;
+
+/// A JSON value
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse a JSON document
+    ///
+    /// Returns None if invalid input, or arrays and objects nested deeper than
+    /// [`MAX_DEPTH`].
+    pub(crate) fn parse(s: &str) -> Option<Json> {
+        let mut parser = Parser { s, i: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.whitespace();
+        match parser.i == s.len() {
+            true => Some(value),
+            false => None,
+        }
+    }
+
+    /// Returns the value of the first member named `key`, if this is an object
+    pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns the string, if this is a string
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the items, if this is an array
+    pub(crate) fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    i: usize,
+    /// Arrays and objects open around the current position
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.i).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.i += 1;
+        }
+    }
+
+    /// Skip a byte if it's the one expected
+    fn eat(&mut self, b: u8) -> bool {
+        self.whitespace();
+        let ret = self.peek() == Some(b);
+        if ret {
+            self.i += 1;
+        }
+        ret
+    }
+
+    fn literal(&mut self, literal: &str, value: Json) -> Option<Json> {
+        let rest = &self.s[self.i..];
+        rest.starts_with(literal).then(|| {
+            self.i += literal.len();
+            value
+        })
+    }
+
+    fn value(&mut self) -> Option<Json> {
+        self.whitespace();
+        match self.peek()? {
+            b'n' => self.literal("null", Json::Null),
+            b't' => self.literal("true", Json::Bool(true)),
+            b'f' => self.literal("false", Json::Bool(false)),
+            b'"' => self.string().map(Json::String),
+            b'[' | b'{' if self.depth == MAX_DEPTH => None,
+            b'[' => {
+                self.depth += 1;
+                let ret = self.array();
+                self.depth -= 1;
+                ret
+            }
+            b'{' => {
+                self.depth += 1;
+                let ret = self.object();
+                self.depth -= 1;
+                ret
+            }
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => None,
+        }
+    }
+
+    fn array(&mut self) -> Option<Json> {
+        self.i += 1;
+        let mut items = Vec::new();
+        if self.eat(b']') {
+            return Some(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat(b']') {
+                return Some(Json::Array(items));
+            } else if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<Json> {
+        self.i += 1;
+        let mut members = Vec::new();
+        if self.eat(b'}') {
+            return Some(Json::Object(members));
+        }
+        loop {
+            self.whitespace();
+            if self.peek() != Some(b'"') {
+                return None;
+            }
+            let name = self.string()?;
+            if !self.eat(b':') {
+                return None;
+            }
+            members.push((name, self.value()?));
+            if self.eat(b'}') {
+                return Some(Json::Object(members));
+            } else if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Json> {
+        let start = self.i;
+        while matches!(
+            self.peek(),
+            Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+        ) {
+            self.i += 1;
+        }
+        self.s[start..self.i].parse().ok().map(Json::Number)
+    }
+
+    /// Returns four hex digits as a number
+    fn hex(&mut self) -> Option<u32> {
+        let digits = self.s.get(self.i..self.i + 4)?;
+        self.i += 4;
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.i += 1;
+        let mut ret = String::new();
+        loop {
+            let c = self.s[self.i..].chars().next()?;
+            self.i += c.len_utf8();
+            match c {
+                '"' => return Some(ret),
+                '\\' => {
+                    let escape = self.peek()?;
+                    self.i += 1;
+                    match escape {
+                        b'"' => ret.push('"'),
+                        b'\\' => ret.push('\\'),
+                        b'/' => ret.push('/'),
+                        b'b' => ret.push('\u{8}'),
+                        b'f' => ret.push('\u{c}'),
+                        b'n' => ret.push('\n'),
+                        b'r' => ret.push('\r'),
+                        b't' => ret.push('\t'),
+                        b'u' => {
+                            let mut code = self.hex()?;
+                            // A surrogate pair, for characters beyond the BMP
+                            if (0xD800..0xDC00).contains(&code) {
+                                if !self.s[self.i..].starts_with("\\u") {
+                                    return None;
+                                }
+                                self.i += 2;
+                                let low = self.hex()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return None;
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+                            ret.push(char::from_u32(code)?);
+                        }
+                        _ => return None,
+                    }
+                }
+                c if c < ' ' => return None,
+                c => ret.push(c),
+            }
+        }
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn json_test() {
+        let json = Json::parse(
+            r#" {"title": "\u5783\u573e", "n": [1, -2.5e1, true, false, null],
+                 "s": "a\"b\\c\/\n\ud840\udc00", "o": {}, "a": []} "#,
+        )
+        .unwrap();
+        assert_eq!(json.get("title").and_then(Json::as_str), Some("垃圾"));
+        assert_eq!(
+            json.get("n").and_then(Json::as_array),
+            Some(
+                &[
+                    Json::Number(1.0),
+                    Json::Number(-25.0),
+                    Json::Bool(true),
+                    Json::Bool(false),
+                    Json::Null
+                ][..]
+            )
+        );
+        assert_eq!(json.get("s").and_then(Json::as_str), Some("a\"b\\c/\n𠀀"));
+        assert_eq!(json.get("o"), Some(&Json::Object(Vec::new())));
+        assert_eq!(json.get("a"), Some(&Json::Array(Vec::new())));
+        assert_eq!(json.get("missing"), None);
+        assert_eq!(
+            Json::parse("\"中文\""),
+            Some(Json::String("中文".to_owned()))
+        );
+
+        for invalid in [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{1: 2}",
+            "\"abc",
+            "tru",
+            "[1] 2",
+            "\"\\x\"",
+            "\"\\ud840\"",
+            "\"a\nb\"",
+        ] {
+            assert_eq!(Json::parse(invalid), None, "{:?}", invalid);
+        }
+
+        // Nesting is limited rather than overflowing the stack
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_some());
+        assert_eq!(Json::parse(&nested(MAX_DEPTH + 1)), None);
+        assert_eq!(Json::parse(&"[".repeat(200_000)), None);
+        assert_eq!(Json::parse(&"{\"a\":".repeat(200_000)), None);
+    }
+}
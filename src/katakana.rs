@@ -0,0 +1,335 @@
+//! Japanese katakana transcription.
+//!
+//! Each initial has a row of kana, one per vowel, and each final picks a column
+//! of that row, adding a glide (`ャ`, `ア`, …) and a tail (`イ`, `ン`, `ー`, …).
+//! By default the unaspirated affricates `zh` and `z` share their kana with
+//! `ch` and `c` (張 チャン, 子 ツー), as is common in Japanese print.
+use crate::{decode_pinyin, decode_zhuyin, lookup_syllable, Notation, Syllable};
+
+/// Options for the conventions some publishers use to keep initials apart
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KatakanaOptions {
+    /// Write the unaspirated `zh` and `z` with voiced kana (ヂ, ヅ) to tell them from `ch` and `c`
+    pub aspiration: bool,
+    /// Underline the first kana of retroflex initials (`zh`, `ch`, `sh`, `r`)
+    pub retroflex: bool,
+}
+
+/// Katakana, such as `チャン`
+///
+/// Katakana can only be rendered; parsing always returns None.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Katakana(pub KatakanaOptions);
+
+impl Notation for Katakana {
+    fn parse(&self, _s: &str) -> Option<Syllable> {
+        None
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_katakana(syllable.to_string(), self.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Vowel {
+    A,
+    I,
+    U,
+    E,
+    O,
+}
+
+#[derive(Clone, Copy)]
+enum Glide {
+    Plain,
+    Y,
+    W,
+}
+
+use Glide::*;
+use Vowel::*;
+
+const UNDERLINE: char = '\u{332}';
+
+/// Returns the kana row of an initial, indexed by vowel
+fn row(initial: &str, options: KatakanaOptions) -> [&'static str; 5] {
+    match initial {
+        "b" => ["バ", "ビ", "ブ", "ベ", "ボ"],
+        "p" => ["パ", "ピ", "プ", "ペ", "ポ"],
+        "m" => ["マ", "ミ", "ム", "メ", "モ"],
+        "f" => ["ファ", "フィ", "フ", "フェ", "フォ"],
+        "d" => ["ダ", "ディ", "ドゥ", "デ", "ド"],
+        "t" => ["タ", "ティ", "トゥ", "テ", "ト"],
+        "n" => ["ナ", "ニ", "ヌ", "ネ", "ノ"],
+        "l" | "r" => ["ラ", "リ", "ル", "レ", "ロ"],
+        "g" => ["ガ", "ギ", "グ", "ゲ", "ゴ"],
+        "k" => ["カ", "キ", "ク", "ケ", "コ"],
+        "h" => ["ハ", "ヒ", "フ", "ヘ", "ホ"],
+        "j" => ["ジャ", "ジ", "ジュ", "ジェ", "ジョ"],
+        "zh" if options.aspiration => ["ヂャ", "ヂ", "ヂュ", "ヂェ", "ヂョ"],
+        "q" | "zh" | "ch" => ["チャ", "チ", "チュ", "チェ", "チョ"],
+        "x" | "sh" => ["シャ", "シ", "シュ", "シェ", "ショ"],
+        "z" if options.aspiration => ["ヅァ", "ヅィ", "ヅ", "ヅェ", "ヅォ"],
+        "z" | "c" => ["ツァ", "ツィ", "ツ", "ツェ", "ツォ"],
+        "s" => ["サ", "スィ", "ス", "セ", "ソ"],
+        _ => ["ア", "イ", "ウ", "エ", "オ"],
+    }
+}
+
+/// Returns the glide, vowel and tail of a final
+fn rhyme(initial: &str, final_: &str) -> (Glide, Vowel, &'static str) {
+    match (initial, final_) {
+        ("z" | "c" | "s", "i") => (Plain, U, "ー"),
+        ("", "ui") => (W, E, "イ"),
+        ("", "un") => (W, E, "ン"),
+        ("", "ueng") => (W, O, "ン"),
+        ("", "io") => (Y, O, ""),
+        (_, "a") => (Plain, A, ""),
+        (_, "o") => (Plain, O, ""),
+        (_, "e") => (Plain, O, "ー"),
+        (_, "ai") => (Plain, A, "イ"),
+        (_, "ei") => (Plain, E, "イ"),
+        (_, "ao") => (Plain, A, "オ"),
+        (_, "ou") => (Plain, O, "ウ"),
+        (_, "an") | (_, "ang") => (Plain, A, "ン"),
+        (_, "en") => (Plain, E, "ン"),
+        (_, "eng") | (_, "ong") => (Plain, O, "ン"),
+        (_, "er") => (Plain, A, "ル"),
+        (_, "i") => (Plain, I, "ー"),
+        (_, "ia") => (Y, A, ""),
+        (_, "ie") => (Y, E, ""),
+        (_, "iao") => (Y, A, "オ"),
+        (_, "iu") => (Y, O, "ウ"),
+        (_, "ian") => (Y, E, "ン"),
+        (_, "in") | (_, "ing") => (Plain, I, "ン"),
+        (_, "iang") => (Y, A, "ン"),
+        (_, "iong") => (Y, O, "ン"),
+        (_, "u") => (Plain, U, "ー"),
+        (_, "ua") => (W, A, ""),
+        (_, "uo") => (W, O, ""),
+        (_, "uai") => (W, A, "イ"),
+        (_, "ui") => (Plain, U, "イ"),
+        (_, "uan") | (_, "uang") => (W, A, "ン"),
+        (_, "un") => (Plain, U, "ン"),
+        (_, "v") => (Y, U, "イ"),
+        (_, "ve") => (Y, U, "エ"),
+        (_, "van") => (Y, U, "アン"),
+        (_, "vn") => (Y, U, "ン"),
+        _ => unreachable!(),
+    }
+}
+
+fn render(consonant: &str, final_: &str, options: KatakanaOptions) -> String {
+    // Syllabic nasals are the moraic nasals of kana
+    match final_ {
+        "m" => return "ム".to_owned(),
+        "n" | "ng" => return "ン".to_owned(),
+        _ => (),
+    }
+
+    let row = row(consonant, options);
+    let (glide, vowel, tail) = rhyme(consonant, final_);
+
+    let mut ret = String::with_capacity(16);
+    match (glide, consonant.is_empty()) {
+        (Plain, _) => ret.push_str(row[vowel as usize]),
+        (Y, true) => ret.push_str(["ヤ", "イ", "ユ", "イエ", "ヨ"][vowel as usize]),
+        (W, true) => ret.push_str(["ワ", "ウィ", "ウ", "ウェ", "ウォ"][vowel as usize]),
+        // ティ, ディ, フィ, スィ and ツィ are already digraphs, so the vowel is
+        // full-size after them (ティアオ), and `iu` is just ウ (ディウ)
+        (Y, false) if row[I as usize].chars().count() > 1 => {
+            ret.push_str(row[I as usize]);
+            if final_ != "iu" {
+                ret.push_str(["ア", "イ", "ウ", "エ", "オ"][vowel as usize]);
+            }
+        }
+        (Y, false) => {
+            ret.push_str(row[I as usize]);
+            ret.push_str(["ャ", "ィ", "ュ", "エ", "ョ"][vowel as usize]);
+        }
+        (W, false) => {
+            ret.push_str(row[U as usize]);
+            ret.push_str(["ア", "イ", "ウ", "エ", "オ"][vowel as usize]);
+        }
+    }
+    ret.push_str(tail);
+
+    if options.retroflex && matches!(consonant, "zh" | "ch" | "sh" | "r") {
+        let first = ret.chars().next().unwrap().len_utf8();
+        ret.insert(first, UNDERLINE);
+    }
+    ret
+}
+
+/// Encode katakana
+///
+/// Katakana transcription has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let options = KatakanaOptions::default();
+/// assert_eq!(encode_katakana("bei3", options), Some("ベイ".to_owned()));
+/// assert_eq!(encode_katakana("jing1", options), Some("ジン".to_owned()));
+///
+/// let options = KatakanaOptions { aspiration: true, ..options };
+/// assert_eq!(encode_katakana("zhang1", options), Some("ヂャン".to_owned()));
+///
+/// assert_eq!(encode_katakana("jing", options), None);
+/// ```
+pub fn encode_katakana<S>(s: S, options: KatakanaOptions) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, _) = lookup_syllable(s.as_ref())?;
+    Some(render(consonant, final_, options))
+}
+
+/// Convert pinyin to katakana
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let options = KatakanaOptions::default();
+/// assert_eq!(pinyin_to_katakana("zhōng", options), Some("チョン".to_owned()));
+/// ```
+pub fn pinyin_to_katakana<S>(s: S, options: KatakanaOptions) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_katakana(decode_pinyin(s)?, options)
+}
+
+/// Convert zhuyin to katakana
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let options = KatakanaOptions::default();
+/// assert_eq!(zhuyin_to_katakana("ㄒㄩㄝˊ", options), Some("シュエ".to_owned()));
+/// ```
+pub fn zhuyin_to_katakana<S>(s: S, options: KatakanaOptions) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_katakana(decode_zhuyin(s)?, options)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_katakana_test() {
+        let options = KatakanaOptions::default();
+        assert_eq!(encode_katakana("bei3", options), s!("ベイ"));
+        assert_eq!(encode_katakana("shang4", options), s!("シャン"));
+        assert_eq!(encode_katakana("hai3", options), s!("ハイ"));
+        assert_eq!(encode_katakana("guo2", options), s!("グオ"));
+        assert_eq!(encode_katakana("zhang1", options), s!("チャン"));
+        assert_eq!(encode_katakana("zi3", options), s!("ツー"));
+        assert_eq!(encode_katakana("si4", options), s!("スー"));
+        assert_eq!(encode_katakana("shi4", options), s!("シー"));
+        assert_eq!(encode_katakana("li3", options), s!("リー"));
+        assert_eq!(encode_katakana("yi1", options), s!("イー"));
+        assert_eq!(encode_katakana("xiao3", options), s!("シャオ"));
+        assert_eq!(encode_katakana("tian1", options), s!("ティエン"));
+        assert_eq!(encode_katakana("xie4", options), s!("シエ"));
+        assert_eq!(encode_katakana("ye4", options), s!("イエ"));
+        assert_eq!(encode_katakana("guang3", options), s!("グアン"));
+        assert_eq!(encode_katakana("shui3", options), s!("シュイ"));
+        assert_eq!(encode_katakana("wei4", options), s!("ウェイ"));
+        assert_eq!(encode_katakana("wen2", options), s!("ウェン"));
+        assert_eq!(encode_katakana("wang2", options), s!("ワン"));
+        assert_eq!(encode_katakana("xue2", options), s!("シュエ"));
+        assert_eq!(encode_katakana("yuan2", options), s!("ユアン"));
+        assert_eq!(encode_katakana("yun2", options), s!("ユン"));
+        assert_eq!(encode_katakana("lv4", options), s!("リュイ"));
+        assert_eq!(encode_katakana("xiong2", options), s!("ション"));
+        assert_eq!(encode_katakana("er4", options), s!("アル"));
+        assert_eq!(encode_katakana("he2", options), s!("ホー"));
+        assert_eq!(encode_katakana("feng1", options), s!("フォン"));
+        // Rows whose i-kana is a digraph take full-size vowels
+        assert_eq!(encode_katakana("tiao1", options), s!("ティアオ"));
+        assert_eq!(encode_katakana("diao4", options), s!("ディアオ"));
+        assert_eq!(encode_katakana("dia3", options), s!("ディア"));
+        assert_eq!(encode_katakana("diu1", options), s!("ディウ"));
+        assert_eq!(encode_katakana("tie1", options), s!("ティエ"));
+        assert_eq!(encode_katakana("dian3", options), s!("ディエン"));
+        assert_eq!(encode_katakana("ting2", options), s!("ティン"));
+
+        assert_eq!(encode_katakana("mao", options), None);
+        assert_eq!(encode_katakana("啊", options), None);
+        assert_eq!(encode_katakana("", options), None);
+    }
+
+    #[test]
+    fn digraph_rows_test() {
+        // f, s and z take no i-finals in pinyin, but their rows spell them the same way
+        let options = KatakanaOptions::default();
+        let aspiration = KatakanaOptions {
+            aspiration: true,
+            ..options
+        };
+        for (consonant, i) in [
+            ("t", "ティ"),
+            ("d", "ディ"),
+            ("f", "フィ"),
+            ("s", "スィ"),
+            ("z", "ツィ"),
+        ] {
+            assert_eq!(render(consonant, "ia", options), format!("{}ア", i));
+            assert_eq!(render(consonant, "iao", options), format!("{}アオ", i));
+            assert_eq!(render(consonant, "iu", options), format!("{}ウ", i));
+            assert_eq!(render(consonant, "iong", options), format!("{}オン", i));
+        }
+        assert_eq!(render("z", "iao", aspiration), "ヅィアオ");
+        // Plain rows keep the small kana
+        assert_eq!(render("n", "iao", options), "ニャオ");
+        assert_eq!(render("l", "iu", options), "リョウ");
+    }
+
+    #[test]
+    fn katakana_options_test() {
+        let plain = KatakanaOptions::default();
+        let aspiration = KatakanaOptions {
+            aspiration: true,
+            ..plain
+        };
+        let retroflex = KatakanaOptions {
+            retroflex: true,
+            ..plain
+        };
+
+        assert_eq!(encode_katakana("zhong1", plain), s!("チョン"));
+        assert_eq!(encode_katakana("chong2", plain), s!("チョン"));
+        assert_eq!(encode_katakana("zhong1", aspiration), s!("ヂョン"));
+        assert_eq!(encode_katakana("chong2", aspiration), s!("チョン"));
+        assert_eq!(encode_katakana("zi3", aspiration), s!("ヅー"));
+        assert_eq!(encode_katakana("ci2", aspiration), s!("ツー"));
+        assert_eq!(encode_katakana("jia1", aspiration), s!("ジャ"));
+
+        assert_eq!(encode_katakana("shi4", retroflex), s!("シ\u{332}ー"));
+        assert_eq!(encode_katakana("xi1", retroflex), s!("シー"));
+        assert_eq!(encode_katakana("chang2", retroflex), s!("チ\u{332}ャン"));
+        assert_eq!(encode_katakana("qiang2", retroflex), s!("チャン"));
+        assert_eq!(encode_katakana("ren2", retroflex), s!("レ\u{332}ン"));
+    }
+
+    #[test]
+    fn katakana_conversion_test() {
+        let options = KatakanaOptions::default();
+        assert_eq!(pinyin_to_katakana("jīng", options), s!("ジン"));
+        assert_eq!(zhuyin_to_katakana("ㄓˋ", options), s!("チー"));
+
+        assert_eq!(pinyin_to_katakana("啊", options), None);
+        assert_eq!(zhuyin_to_katakana("ㄩㄝㄝ", options), None);
+    }
+}
@@ -0,0 +1,462 @@
+//! Zhuyin keyboard layouts.
+//!
+//! Each layout puts the 37 zhuyin symbols and 4 tone marks on QWERTY keys, and
+//! a syllable is typed symbol by symbol, then a tone key, or space for the
+//! first tone. Hsu puts several symbols on one key (`j` is `ㄐ`, `ㄓ` and `ˋ`),
+//! so reading keys looks for the spelling that gives valid syllables.
+use crate::{
+    lookup_syllable, split_numbered_word, split_zhuyin_word, Lattice, Notation, Pinyin, Syllable,
+    Zhuyin,
+};
+
+/// The zhuyin symbols and tone marks, in the order of each layout's keys
+static SYMBOLS: &str =
+    "ㄅㄆㄇㄈㄉㄊㄋㄌㄍㄎㄏㄐㄑㄒㄓㄔㄕㄖㄗㄘㄙㄧㄨㄩㄚㄛㄜㄝㄞㄟㄠㄡㄢㄣㄤㄥㄦ˙ˊˇˋ";
+static TONE_MARKS: &str = "˙ˊˇˋ";
+
+static DACHEN_KEYS: &str = "1qaz2wsxedcrfv5tgbyhnujm8ik,9ol.0p;/-7634";
+static ETEN_KEYS: &str = "bpmfdtnlvkhg7c,./j;'sexuaorwiqzy890-=1234";
+static HSU_KEYS: &str = "bpmfdtnlgkhjvcjvcrzasexuyhgeiawomnkllsdfj";
+static IBM_KEYS: &str = "1234567890-qwertyuiopasdfghjkl;zxcvbn/m,.";
+
+/// A keyboard layout for typing zhuyin
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyboardLayout {
+    /// 大千, the layout printed on most keyboards in Taiwan
+    #[default]
+    Dachen,
+    /// 倚天
+    Eten,
+    /// 許氏
+    Hsu,
+    /// IBM
+    Ibm,
+}
+
+impl KeyboardLayout {
+    fn keys(self) -> &'static str {
+        match self {
+            KeyboardLayout::Dachen => DACHEN_KEYS,
+            KeyboardLayout::Eten => ETEN_KEYS,
+            KeyboardLayout::Hsu => HSU_KEYS,
+            KeyboardLayout::Ibm => IBM_KEYS,
+        }
+    }
+
+    /// Returns the key for a zhuyin symbol or tone mark
+    fn key(self, symbol: char) -> Option<char> {
+        let i = SYMBOLS.chars().position(|c| c == symbol)?;
+        self.keys().chars().nth(i)
+    }
+
+    /// Returns every zhuyin symbol or tone mark on a key, most likely first
+    pub(crate) fn symbols(self, key: char) -> Vec<char> {
+        let mut ret: Vec<char> = SYMBOLS
+            .chars()
+            .zip(self.keys().chars())
+            .filter(|&(_, k)| k == key)
+            .map(|(symbol, _)| symbol)
+            .collect();
+        // ㄦ is only ever typed alone, where it is far more common than ㄥ
+        if let Some(i) = ret.iter().position(|&c| c == 'ㄦ') {
+            ret[..=i].rotate_right(1);
+        }
+        ret
+    }
+}
+
+/// Convert zhuyin to the keys that type it
+///
+/// Syllables may be written together or separated by whitespace. A syllable
+/// without a tone mark ends with space, the first tone key.
+/// Returns None if invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let zhuyin = encode_zhuyin("ni3").unwrap() + &encode_zhuyin("hao3").unwrap();
+/// assert_eq!(zhuyin_to_keys(zhuyin, KeyboardLayout::Dachen), Some("su3cl3".to_owned()));
+/// assert_eq!(zhuyin_to_keys("ㄓㄨㄥ", KeyboardLayout::Hsu), Some("jxl ".to_owned()));
+/// ```
+pub fn zhuyin_to_keys<S>(s: S, layout: KeyboardLayout) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for word in s.as_ref().split_whitespace() {
+        for syllable in split_zhuyin_word(word)? {
+            ret.extend(
+                syllable
+                    .chars()
+                    .map(|c| layout.key(c))
+                    .collect::<Option<Vec<_>>>()?,
+            );
+            if !syllable.ends_with(|c| TONE_MARKS.contains(c)) {
+                ret.push(' ');
+            }
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Returns true if `zhuyin` is a syllable, exactly as zhuyin writes it
+pub(crate) fn is_syllable(zhuyin: &str) -> bool {
+    Zhuyin
+        .parse(zhuyin)
+        // ㄦ˙ is the erhua "r5", which isn't in the syllable table
+        .filter(|syllable| syllable.is_valid() || syllable.rhyme == "r")
+        .and_then(|syllable| Zhuyin.render(&syllable))
+        .is_some_and(|rendered| rendered == zhuyin)
+}
+
+/// Spell the symbol keys of one syllable, trying every symbol each key can type
+fn spell(keys: &[char], tone: &str, layout: KeyboardLayout, syllable: &mut String) -> bool {
+    let Some((&key, rest)) = keys.split_first() else {
+        syllable.push_str(tone);
+        let valid = is_syllable(syllable);
+        syllable.truncate(syllable.len() - tone.len());
+        return valid;
+    };
+
+    let mut symbols = layout.symbols(key);
+    if syllable.is_empty() && rest.is_empty() {
+        // Alone, ㄇ, ㄋ and ㄫ are the rare syllabic nasals, so they come last
+        symbols.sort_by_key(|&symbol| matches!(symbol, 'ㄇ' | 'ㄋ' | 'ㄫ'));
+    }
+    for symbol in symbols {
+        if TONE_MARKS.contains(symbol) {
+            continue;
+        }
+        syllable.push(symbol);
+        if spell(rest, tone, layout, syllable) {
+            return true;
+        }
+        syllable.pop();
+    }
+    false
+}
+
+/// Read the keys from `pos` on as syllables, appending them to `syllables`
+///
+/// `dead_ends` marks the positions already known not to read.
+fn read(
+    keys: &[char],
+    pos: usize,
+    layout: KeyboardLayout,
+    syllables: &mut Vec<String>,
+    dead_ends: &mut [bool],
+) -> bool {
+    if pos == keys.len() {
+        return true;
+    } else if dead_ends[pos] {
+        return false;
+    } else if keys[pos] == ' ' {
+        // A space can't start a syllable, so it only separates words here
+        return read(keys, pos + 1, layout, syllables, dead_ends);
+    }
+
+    // A syllable is one to three symbols, then a tone key
+    for len in (1..=3).rev().filter(|&len| pos + len < keys.len()) {
+        let tones: Vec<String> = match keys[pos + len] {
+            ' ' => vec![String::new()],
+            key => layout
+                .symbols(key)
+                .into_iter()
+                .filter(|&c| TONE_MARKS.contains(c))
+                .map(String::from)
+                .collect(),
+        };
+
+        for tone in tones {
+            let mut syllable = String::with_capacity(12);
+            if spell(&keys[pos..pos + len], &tone, layout, &mut syllable) {
+                syllable.push_str(&tone);
+                syllables.push(syllable);
+                if read(keys, pos + len + 1, layout, syllables, dead_ends) {
+                    return true;
+                }
+                syllables.pop();
+            }
+        }
+    }
+
+    dead_ends[pos] = true;
+    false
+}
+
+/// Read keys as zhuyin syllables
+fn keys_to_syllables(s: &str, layout: KeyboardLayout) -> Option<Vec<String>> {
+    let keys: Vec<char> = s.to_lowercase().chars().collect();
+    if keys.is_empty() {
+        return None;
+    }
+
+    let mut ret = Vec::new();
+    if !read(&keys, 0, layout, &mut ret, &mut vec![false; keys.len()]) {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Convert keys typed on a zhuyin layout to zhuyin
+///
+/// Every syllable must end with a tone key, or space for the first tone; other
+/// spaces are ignored. Where
+/// keys can be read more than one way, longer syllables are tried first, and an
+/// initial before a final; so on Hsu a lone `a` is `ㄘ` rather than `ㄟ`, and
+/// `e` is `ㄧ` rather than `ㄝ`. Returns None if invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(keys_to_zhuyin("su3cl3", KeyboardLayout::Dachen), Some("ㄋㄧˇㄏㄠˇ".to_owned()));
+/// assert_eq!(keys_to_zhuyin("jxl ", KeyboardLayout::Hsu), Some("ㄓㄨㄥ".to_owned()));
+///
+/// assert_eq!(keys_to_zhuyin("su", KeyboardLayout::Dachen), None);
+/// ```
+pub fn keys_to_zhuyin<S>(s: S, layout: KeyboardLayout) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    Some(keys_to_syllables(s.as_ref(), layout)?.concat())
+}
+
+/// Zhuyin recovered from keys typed with the input method off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutRepair {
+    /// The layout the keys were typed on
+    pub layout: KeyboardLayout,
+    /// The syllables typed
+    pub syllables: Vec<Syllable>,
+    /// The syllables as zhuyin, written together
+    pub zhuyin: String,
+    /// The syllables as pinyin, separated by spaces
+    pub pinyin: String,
+}
+
+/// Returns true if every word reads as pinyin syllables, numbered or not
+fn reads_as_pinyin(s: &str) -> bool {
+    s.split_whitespace().all(|word| {
+        let word = word.to_lowercase();
+        if let Some(syllables) = split_numbered_word(&word) {
+            return syllables.iter().all(|s| lookup_syllable(s).is_some());
+        }
+
+        Lattice::new(&word, false).is_complete()
+    })
+}
+
+/// Returns true if keys read as syllables look typed on purpose rather than
+/// being an English word that happens to read
+///
+/// Some tone key besides space must have been typed: one that isn't a letter,
+/// or letters for at least two syllables.
+fn has_tone_keys(syllables: &[String], layout: KeyboardLayout) -> bool {
+    let keys: Vec<char> = syllables
+        .iter()
+        .filter_map(|syllable| syllable.chars().last())
+        .filter(|&c| TONE_MARKS.contains(c))
+        .filter_map(|mark| layout.key(mark))
+        .collect();
+    keys.iter().any(|key| !key.is_ascii_alphabetic()) || keys.len() >= 2
+}
+
+/// Recognize zhuyin keys typed with the input method off
+///
+/// If an ASCII string doesn't read as pinyin but reads as keys on a zhuyin
+/// layout, returns the zhuyin and pinyin it was meant to be. Layouts are tried
+/// in the order Dachen, ETen, Hsu, IBM. A final space for the first tone may be
+/// missing, but some other tone key must have been typed: a key that isn't a
+/// letter, or letter tone keys ending at least two syllables. Plain English
+/// words such as `ok` or `go go` read as first-tone syllables on some layout,
+/// and are left alone. Returns None if the string doesn't look like mistyped
+/// zhuyin.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let repair = detect_wrong_layout("ji3g4").unwrap();
+/// assert_eq!(repair.layout, KeyboardLayout::Dachen);
+/// assert_eq!(repair.zhuyin, "ㄨㄛˇㄕˋ");
+/// assert_eq!(repair.pinyin, "wǒ shì");
+///
+/// assert_eq!(detect_wrong_layout("wo3 shi4"), None);
+/// ```
+pub fn detect_wrong_layout<S>(s: S) -> Option<LayoutRepair>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().trim_start();
+    if s.trim_end().is_empty() || !s.is_ascii() || reads_as_pinyin(s) {
+        return None;
+    }
+
+    let with_space = format!("{} ", s);
+    for layout in [
+        KeyboardLayout::Dachen,
+        KeyboardLayout::Eten,
+        KeyboardLayout::Hsu,
+        KeyboardLayout::Ibm,
+    ] {
+        let Some(zhuyin) =
+            keys_to_syllables(s, layout).or_else(|| keys_to_syllables(&with_space, layout))
+        else {
+            continue;
+        };
+        if !has_tone_keys(&zhuyin, layout) {
+            continue;
+        }
+        let syllables: Vec<Syllable> = zhuyin
+            .iter()
+            .map(|zhuyin| Zhuyin.parse(zhuyin))
+            .collect::<Option<_>>()?;
+        let pinyin: Vec<String> = syllables
+            .iter()
+            .map(|syllable| Pinyin.render(syllable))
+            .collect::<Option<_>>()?;
+
+        return Some(LayoutRepair {
+            layout,
+            syllables,
+            zhuyin: zhuyin.concat(),
+            pinyin: pinyin.join(" "),
+        });
+    }
+    None
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn layout_test() {
+        for layout in [
+            KeyboardLayout::Dachen,
+            KeyboardLayout::Eten,
+            KeyboardLayout::Hsu,
+            KeyboardLayout::Ibm,
+        ] {
+            assert_eq!(layout.keys().chars().count(), SYMBOLS.chars().count());
+        }
+    }
+
+    #[test]
+    fn zhuyin_to_keys_test() {
+        use KeyboardLayout::*;
+
+        assert_eq!(zhuyin_to_keys("ㄋㄧˇㄏㄠˇ", Dachen), s!("su3cl3"));
+        assert_eq!(zhuyin_to_keys("ㄓㄨㄥ ㄍㄨㄛˊ", Dachen), s!("5j/ eji6"));
+        assert_eq!(zhuyin_to_keys("ㄇㄣ˙", Dachen), s!("ap7"));
+        assert_eq!(zhuyin_to_keys("ㄦˋ", Dachen), s!("-4"));
+        assert_eq!(zhuyin_to_keys("ㄋㄧˇㄏㄠˇ", Eten), s!("ne3hz3"));
+        assert_eq!(zhuyin_to_keys("ㄓㄨㄥㄍㄨㄛˊ", Eten), s!(",x- vxo2"));
+        assert_eq!(zhuyin_to_keys("ㄇㄣ˙", Eten), s!("m91"));
+        assert_eq!(zhuyin_to_keys("ㄋㄧˇㄏㄠˇ", Hsu), s!("nefhwf"));
+        assert_eq!(zhuyin_to_keys("ㄓㄨㄥㄍㄨㄛˊ", Hsu), s!("jxl gxhd"));
+        assert_eq!(zhuyin_to_keys("ㄋㄧˇㄏㄠˇ", Ibm), s!("7a,-;,"));
+
+        assert_eq!(zhuyin_to_keys("ㄋㄧˇㄏ", Dachen), None);
+        assert_eq!(zhuyin_to_keys("ni3", Dachen), None);
+        assert_eq!(zhuyin_to_keys("", Dachen), None);
+    }
+
+    #[test]
+    fn keys_to_zhuyin_test() {
+        use KeyboardLayout::*;
+
+        assert_eq!(keys_to_zhuyin("su3cl3", Dachen), s!("ㄋㄧˇㄏㄠˇ"));
+        assert_eq!(keys_to_zhuyin("5j/ eji6", Dachen), s!("ㄓㄨㄥㄍㄨㄛˊ"));
+        assert_eq!(keys_to_zhuyin("ap7", Dachen), s!("ㄇㄣ˙"));
+        assert_eq!(keys_to_zhuyin(" su3 cl3 ", Dachen), s!("ㄋㄧˇㄏㄠˇ"));
+        assert_eq!(keys_to_zhuyin("ne3hz3", Eten), s!("ㄋㄧˇㄏㄠˇ"));
+        assert_eq!(keys_to_zhuyin(",x- vxo2", Eten), s!("ㄓㄨㄥㄍㄨㄛˊ"));
+        assert_eq!(keys_to_zhuyin("7a,-;,", Ibm), s!("ㄋㄧˇㄏㄠˇ"));
+        // Hsu keys are read by what makes a syllable
+        assert_eq!(keys_to_zhuyin("nefhwf", Hsu), s!("ㄋㄧˇㄏㄠˇ"));
+        assert_eq!(keys_to_zhuyin("jxl gxhd", Hsu), s!("ㄓㄨㄥㄍㄨㄛˊ"));
+        assert_eq!(keys_to_zhuyin("jef", Hsu), s!("ㄐㄧˇ"));
+        assert_eq!(keys_to_zhuyin("jj", Hsu), s!("ㄓˋ"));
+        assert_eq!(keys_to_zhuyin("mj", Hsu), s!("ㄢˋ"));
+        assert_eq!(keys_to_zhuyin("lj", Hsu), s!("ㄦˋ"));
+        assert_eq!(keys_to_zhuyin("af", Hsu), s!("ㄘˇ"));
+        assert_eq!(keys_to_zhuyin("laj", Hsu), s!("ㄌㄟˋ"));
+        assert_eq!(keys_to_zhuyin("Su3", Dachen), s!("ㄋㄧˇ"));
+
+        // Layouts with a key per symbol read back every syllable, but ㄫ has no key
+        for layout in [Dachen, Eten, Ibm] {
+            for syllable in crate::syllables().filter(|&s| s != "ng") {
+                for tone in 1..=5 {
+                    let zhuyin = crate::encode_zhuyin(format!("{}{}", syllable, tone)).unwrap();
+                    let keys = zhuyin_to_keys(&zhuyin, layout).unwrap();
+                    assert_eq!(keys_to_zhuyin(keys, layout), Some(zhuyin));
+                }
+            }
+        }
+
+        assert_eq!(keys_to_zhuyin("su", Dachen), None);
+        assert_eq!(keys_to_zhuyin("3", Dachen), None);
+        assert_eq!(keys_to_zhuyin("su3c", Dachen), None);
+        assert_eq!(keys_to_zhuyin("", Dachen), None);
+    }
+
+    #[test]
+    fn detect_wrong_layout_test() {
+        use KeyboardLayout::*;
+
+        let repair = detect_wrong_layout("su3cl3").unwrap();
+        assert_eq!(repair.layout, Dachen);
+        assert_eq!(repair.zhuyin, "ㄋㄧˇㄏㄠˇ");
+        assert_eq!(repair.pinyin, "nǐ hǎo");
+        assert_eq!(
+            repair.syllables,
+            [
+                Syllable::new("ni3").unwrap(),
+                Syllable::new("hao3").unwrap()
+            ]
+        );
+
+        // A final first tone space is often lost
+        let repair = detect_wrong_layout("5j/ eji6 5j/").unwrap();
+        assert_eq!(repair.zhuyin, "ㄓㄨㄥㄍㄨㄛˊㄓㄨㄥ");
+        assert_eq!(repair.pinyin, "zhōng guó zhōng");
+
+        let repair = detect_wrong_layout("ne3hz3").unwrap();
+        assert_eq!(repair.layout, Eten);
+        assert_eq!(repair.pinyin, "nǐ hǎo");
+
+        let repair = detect_wrong_layout("nefhwf").unwrap();
+        assert_eq!(repair.layout, Hsu);
+        assert_eq!(repair.zhuyin, "ㄋㄧˇㄏㄠˇ");
+        assert_eq!(detect_wrong_layout("mad"), None);
+
+        // Pinyin is left alone
+        assert_eq!(detect_wrong_layout("ni3hao3"), None);
+        assert_eq!(detect_wrong_layout("Nihao"), None);
+        assert_eq!(detect_wrong_layout("xi'an"), None);
+        assert_eq!(detect_wrong_layout("nǐ hǎo"), None);
+        // Text that isn't zhuyin either
+        assert_eq!(detect_wrong_layout("hello world"), None);
+        // English words and chat tokens that read as first-tone syllables
+        for english in [
+            "ok", "hi", "go", "go go", "up", "is", "omg", "idk", "lol", "brb", "yes", "no", "the",
+            "and", "you", "thx", "ok thx", "lmao", "asap", "btw", "fyi", "plz", "hey", "sure",
+            "nope", "yeah", "wow", "ty", "np", "gg", "bye", "ok ok",
+        ] {
+            assert_eq!(detect_wrong_layout(english), None, "{}", english);
+        }
+        // Hsu tone keys are letters, so two are needed
+        assert_eq!(detect_wrong_layout("nef"), None);
+        assert_eq!(detect_wrong_layout("   "), None);
+        assert_eq!(detect_wrong_layout(""), None);
+    }
+}
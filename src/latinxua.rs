@@ -0,0 +1,267 @@
+//! Latinxua Sin Wenz.
+//!
+//! Latinxua writes no tones, spells `h` as `x` and `r` as `rh`, and writes the
+//! apical vowel not at all (`zh`, `s`). `j`, `q` and `x` are written `g`, `k`,
+//! `x` or, in the sharp convention, `z`, `c`, `s`. `ü` is `y`, and the zero
+//! initial is spelt with `j` and `w` (`jou`, `wei`).
+use crate::{decode_pinyin, decode_zhuyin, lookup_syllable, Notation, Syllable, SYLLABLES};
+
+/// How `j`, `q` and `x` are written
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Palatals {
+    /// As `g`, `k`, `x` (gi, ki, xi)
+    #[default]
+    Rounded,
+    /// As `z`, `c`, `s` (zi, ci, si)
+    Sharp,
+}
+
+/// Latinxua Sin Wenz, such as `zhung`
+///
+/// Parsing gives the first candidate of `decode_latinxua`, with the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Latinxua(pub Palatals);
+
+impl Notation for Latinxua {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(decode_latinxua(s).first()?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_latinxua(syllable.to_string(), self.0)
+    }
+}
+
+/// Returns the Latinxua spelling of an initial
+fn initial(initial: &str, palatals: Palatals) -> &'static str {
+    match (initial, palatals) {
+        ("j", Palatals::Rounded) => "g",
+        ("q", Palatals::Rounded) => "k",
+        ("x", Palatals::Rounded) => "x",
+        ("j", Palatals::Sharp) => "z",
+        ("q", Palatals::Sharp) => "c",
+        ("x", Palatals::Sharp) => "s",
+        ("b", _) => "b",
+        ("p", _) => "p",
+        ("m", _) => "m",
+        ("f", _) => "f",
+        ("d", _) => "d",
+        ("t", _) => "t",
+        ("n", _) => "n",
+        ("l", _) => "l",
+        ("g", _) => "g",
+        ("k", _) => "k",
+        ("h", _) => "x",
+        ("zh", _) => "zh",
+        ("ch", _) => "ch",
+        ("sh", _) => "sh",
+        ("r", _) => "rh",
+        ("z", _) => "z",
+        ("c", _) => "c",
+        ("s", _) => "s",
+        _ => "",
+    }
+}
+
+/// Returns the Latinxua spelling of a final, which depends on whether there's an initial
+fn rhyme(initial: &str, final_: &'static str) -> &'static str {
+    match (initial, final_) {
+        ("zh" | "ch" | "sh" | "r" | "z" | "c" | "s", "i") => "",
+        ("", "io") => "jo",
+        ("", "ia") => "ja",
+        ("", "ie") => "je",
+        ("", "iao") => "jao",
+        ("", "iu") => "jou",
+        ("", "ian") => "jan",
+        ("", "iang") => "jang",
+        ("", "iong") => "jung",
+        ("", "ua") => "wa",
+        ("", "uo") => "wo",
+        ("", "uai") => "wai",
+        ("", "ui") => "wei",
+        ("", "uan") => "wan",
+        ("", "un") => "wen",
+        ("", "uang") => "wang",
+        ("", "ueng") => "weng",
+        ("b" | "p" | "m" | "f", "o") => "o",
+        (_, "uo") => "o",
+        (_, "o") => "o",
+        (_, "ong") => "ung",
+        (_, "iong") => "iung",
+        (_, "er") => "r",
+        (_, "v") => "y",
+        (_, "ve") => "ye",
+        (_, "van") => "yan",
+        (_, "vn") => "yn",
+        (_, final_) => final_,
+    }
+}
+
+/// Spell a syllable from its initial and final
+fn spell(consonant: &str, final_: &'static str, palatals: Palatals) -> String {
+    let mut ret = initial(consonant, palatals).to_owned();
+    ret.push_str(rhyme(consonant, final_));
+    ret
+}
+
+/// Encode Latinxua Sin Wenz
+///
+/// Latinxua has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_latinxua("zhong1", Palatals::Rounded), Some("zhung".to_owned()));
+/// assert_eq!(encode_latinxua("xin1", Palatals::Rounded), Some("xin".to_owned()));
+/// assert_eq!(encode_latinxua("xin1", Palatals::Sharp), Some("sin".to_owned()));
+///
+/// assert_eq!(encode_latinxua("xin", Palatals::Rounded), None);
+/// ```
+pub fn encode_latinxua<S>(s: S, palatals: Palatals) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, _) = lookup_syllable(s.as_ref())?;
+    Some(spell(consonant, final_, palatals))
+}
+
+/// Decode Latinxua Sin Wenz
+///
+/// Several syllables can share a spelling, so every candidate is returned,
+/// always with the 5th tone. Both ways of writing `j`, `q` and `x` are read, as
+/// is `ы` for the apical vowel. Returns an empty list if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_latinxua("Xan"), vec!["han5".to_owned()]);
+/// assert_eq!(decode_latinxua("lo"), vec!["luo5".to_owned(), "lo5".to_owned()]);
+/// ```
+pub fn decode_latinxua<S>(s: S) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().to_lowercase();
+    let s = match s.strip_suffix('ы') {
+        Some(apical @ ("zh" | "ch" | "sh" | "rh" | "z" | "c" | "s")) => apical,
+        _ => &s,
+    };
+    SYLLABLES
+        .entries()
+        .filter(|(_, (consonant, final_))| {
+            [Palatals::Rounded, Palatals::Sharp]
+                .iter()
+                .any(|&palatals| spell(consonant, final_, palatals) == s)
+        })
+        .map(|(syllable, _)| format!("{}5", syllable))
+        .collect()
+}
+
+/// Convert pinyin to Latinxua Sin Wenz
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_latinxua("guó", Palatals::Rounded), Some("go".to_owned()));
+/// ```
+pub fn pinyin_to_latinxua<S>(s: S, palatals: Palatals) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_latinxua(decode_pinyin(s)?, palatals)
+}
+
+/// Convert zhuyin to Latinxua Sin Wenz
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_latinxua("ㄖㄣˊ", Palatals::Rounded), Some("rhen".to_owned()));
+/// ```
+pub fn zhuyin_to_latinxua<S>(s: S, palatals: Palatals) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_latinxua(decode_zhuyin(s)?, palatals)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_latinxua_test() {
+        let rounded = Palatals::Rounded;
+        assert_eq!(encode_latinxua("bo1", rounded), s!("bo"));
+        assert_eq!(encode_latinxua("guo2", rounded), s!("go"));
+        assert_eq!(encode_latinxua("shuo1", rounded), s!("sho"));
+        assert_eq!(encode_latinxua("wo3", rounded), s!("wo"));
+        assert_eq!(encode_latinxua("hao3", rounded), s!("xao"));
+        assert_eq!(encode_latinxua("ren2", rounded), s!("rhen"));
+        assert_eq!(encode_latinxua("er4", rounded), s!("r"));
+        assert_eq!(encode_latinxua("zhi1", rounded), s!("zh"));
+        assert_eq!(encode_latinxua("ri4", rounded), s!("rh"));
+        assert_eq!(encode_latinxua("si1", rounded), s!("s"));
+        assert_eq!(encode_latinxua("dong1", rounded), s!("dung"));
+        assert_eq!(encode_latinxua("xiong2", rounded), s!("xiung"));
+        assert_eq!(encode_latinxua("jia1", rounded), s!("gia"));
+        assert_eq!(encode_latinxua("qu4", rounded), s!("ky"));
+        assert_eq!(encode_latinxua("lve4", rounded), s!("lye"));
+        assert_eq!(encode_latinxua("yi1", rounded), s!("i"));
+        assert_eq!(encode_latinxua("yan2", rounded), s!("jan"));
+        assert_eq!(encode_latinxua("you3", rounded), s!("jou"));
+        assert_eq!(encode_latinxua("yong4", rounded), s!("jung"));
+        assert_eq!(encode_latinxua("yuan2", rounded), s!("yan"));
+        assert_eq!(encode_latinxua("wu3", rounded), s!("u"));
+        assert_eq!(encode_latinxua("wei4", rounded), s!("wei"));
+        assert_eq!(encode_latinxua("hui2", rounded), s!("xui"));
+        assert_eq!(encode_latinxua("wen2", rounded), s!("wen"));
+
+        let sharp = Palatals::Sharp;
+        assert_eq!(encode_latinxua("jia1", sharp), s!("zia"));
+        assert_eq!(encode_latinxua("qu4", sharp), s!("cy"));
+        assert_eq!(encode_latinxua("xi1", sharp), s!("si"));
+        assert_eq!(encode_latinxua("hao3", sharp), s!("xao"));
+
+        assert_eq!(encode_latinxua("bo", rounded), None);
+        assert_eq!(encode_latinxua("r5", rounded), None);
+        assert_eq!(encode_latinxua("", rounded), None);
+    }
+
+    #[test]
+    fn decode_latinxua_test() {
+        assert_eq!(decode_latinxua("zhung"), vec!["zhong5"]);
+        assert_eq!(decode_latinxua("Go"), vec!["guo5"]);
+        assert_eq!(decode_latinxua("xi"), vec!["xi5"]);
+        assert_eq!(decode_latinxua("si"), vec!["xi5"]);
+        assert_eq!(decode_latinxua("s"), vec!["si5"]);
+        assert_eq!(decode_latinxua("sы"), vec!["si5"]);
+        assert_eq!(decode_latinxua("zhы"), vec!["zhi5"]);
+        assert_eq!(decode_latinxua("rh"), vec!["ri5"]);
+        assert_eq!(decode_latinxua("r"), vec!["er5"]);
+        assert_eq!(decode_latinxua("jan"), vec!["yan5"]);
+        assert_eq!(decode_latinxua("yan"), vec!["yvan5"]);
+        assert_eq!(decode_latinxua("lo"), vec!["luo5", "lo5"]);
+
+        assert!(decode_latinxua("ы").is_empty());
+        assert!(decode_latinxua("zhong").is_empty());
+        assert!(decode_latinxua("").is_empty());
+    }
+
+    #[test]
+    fn latinxua_conversion_test() {
+        assert_eq!(pinyin_to_latinxua("ān", Palatals::Rounded), s!("an"));
+        assert_eq!(pinyin_to_latinxua("xué", Palatals::Rounded), s!("xye"));
+        assert_eq!(zhuyin_to_latinxua("ㄑㄧㄥ", Palatals::Sharp), s!("cing"));
+        assert_eq!(zhuyin_to_latinxua("ㄏㄨㄛˇ", Palatals::Rounded), s!("xo"));
+
+        assert_eq!(pinyin_to_latinxua("啊", Palatals::Rounded), None);
+        assert_eq!(zhuyin_to_latinxua("ㄩㄝㄝ", Palatals::Rounded), None);
+    }
+}
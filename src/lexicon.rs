@@ -0,0 +1,247 @@
+//! Reading Hanzi text with a dictionary.
+//!
+//! A [`Lexicon`] gives the readings of words. [`annotate`] splits text into
+//! the longest words the lexicon knows, left to right, so polyphones are read
+//! as part of their word: 银行 is yínháng, while 行走 is xíngzǒu.
+use crate::{Notation, Syllable};
+
+/// Something that knows the readings of words
+pub trait Lexicon {
+    /// Returns the readings of a word, most likely first
+    ///
+    /// Each reading has one syllable per character. Returns an empty list if
+    /// the word is unknown.
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>>;
+
+    /// Returns the number of characters of the longest word
+    fn longest_word(&self) -> usize;
+
+    /// Returns every word with a reading, in no particular order
+    fn words(&self) -> Vec<&str>;
+}
+
+impl<L: Lexicon + ?Sized> Lexicon for &L {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        (**self).readings(word)
+    }
+
+    fn longest_word(&self) -> usize {
+        (**self).longest_word()
+    }
+
+    fn words(&self) -> Vec<&str> {
+        (**self).words()
+    }
+}
+
+/// Two lexicons read as one, the first taking priority
+///
+/// The readings of the first lexicon come before those of the second, so a
+/// user dictionary layered over a general one decides how its words are read.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let cedict = Cedict::parse("曾 曾 [ceng2] /once/\n");
+/// let user = UserDict::parse("曾\tzeng1\n").unwrap();
+/// let layered = Layered::new(&user, &cedict);
+/// assert_eq!(annotate_text("曾", &layered, &Pinyin), "zēng");
+/// assert_eq!(layered.readings("曾").len(), 2);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Layered<A, B> {
+    /// The lexicon taking priority
+    pub first: A,
+    /// The lexicon read after the first
+    pub second: B,
+}
+
+impl<A: Lexicon, B: Lexicon> Layered<A, B> {
+    /// Layer one lexicon over another
+    pub fn new(first: A, second: B) -> Layered<A, B> {
+        Layered { first, second }
+    }
+}
+
+impl<A: Lexicon, B: Lexicon> Lexicon for Layered<A, B> {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        let mut ret = self.first.readings(word);
+        for reading in self.second.readings(word) {
+            if !ret.contains(&reading) {
+                ret.push(reading);
+            }
+        }
+        ret
+    }
+
+    fn longest_word(&self) -> usize {
+        self.first.longest_word().max(self.second.longest_word())
+    }
+
+    fn words(&self) -> Vec<&str> {
+        let mut ret = self.first.words();
+        ret.extend(self.second.words());
+        ret.sort_unstable();
+        ret.dedup();
+        ret
+    }
+}
+
+/// A word of some text, with its reading if the lexicon knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    /// The text of the word
+    pub text: String,
+    /// One syllable per character, or empty if the text isn't a known word
+    pub syllables: Vec<Syllable>,
+}
+
+impl Annotation {
+    /// Returns true if the word has a reading
+    pub fn is_annotated(&self) -> bool {
+        !self.syllables.is_empty()
+    }
+
+    /// Render the reading in a notation, with the syllables written together
+    ///
+    /// In pinyin, syllables starting with `a`, `o` or `e` after the first are
+    /// set off with an apostrophe, as in xī'ān. In zhuyin, a syllable without a
+    /// tone mark is followed by a space, so it isn't read with the next one.
+    /// Returns None if the word has no reading, or a syllable can't be written
+    /// in the notation.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let annotation = Annotation {
+    ///     text: "西安".to_owned(),
+    ///     syllables: vec![Syllable::new("xi1").unwrap(), Syllable::new("an1").unwrap()],
+    /// };
+    /// assert_eq!(annotation.render(&Pinyin), Some("xī'ān".to_owned()));
+    /// assert_eq!(annotation.render(&Zhuyin), Some("ㄒㄧ ㄢ".to_owned()));
+    /// ```
+    pub fn render<N: Notation + ?Sized>(&self, notation: &N) -> Option<String> {
+        if !self.is_annotated() {
+            return None;
+        }
+
+        let mut ret = String::new();
+        for syllable in &self.syllables {
+            let rendered = notation.render(syllable)?;
+            let vowel = rendered
+                .chars()
+                .next()
+                .and_then(|c| c.to_lowercase().next())
+                .is_some_and(|c| "aāáǎàoōóǒòeēéěèê".contains(c));
+            if ret.ends_with(|c| ('ㄅ'..='ㄩ').contains(&c)) {
+                ret.push(' ');
+            } else if !ret.is_empty() && vowel {
+                ret.push('\'');
+            }
+            ret.push_str(&rendered);
+        }
+        Some(ret)
+    }
+}
+
+/// Split text into words and read them with a lexicon
+///
+/// At each position the longest word the lexicon knows is taken, and its most
+/// likely reading. Text the lexicon doesn't know, such as punctuation, is kept
+/// in one annotation without syllables.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let cedict = Cedict::parse(
+///     "銀行 银行 [yin2 hang2] /bank/\n\
+///      行 行 [xing2] /to walk/\n\
+///      銀 银 [yin2] /silver/\n",
+/// );
+/// let words: Vec<String> = annotate("银行行", &cedict)
+///     .iter()
+///     .map(|annotation| annotation.render(&Pinyin).unwrap())
+///     .collect();
+/// assert_eq!(words, ["yínháng", "xíng"]);
+/// ```
+pub fn annotate<L: Lexicon + ?Sized>(text: &str, lexicon: &L) -> Vec<Annotation> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte = |i: usize| chars.get(i).map_or(text.len(), |&(byte, _)| byte);
+
+    let mut ret: Vec<Annotation> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let longest = lexicon.longest_word().min(chars.len() - i);
+        let word = (1..=longest).rev().find_map(|len| {
+            let word = &text[byte(i)..byte(i + len)];
+            let reading = lexicon
+                .readings(word)
+                .into_iter()
+                .find(|reading| reading.len() == len)?;
+            Some((len, word, reading))
+        });
+
+        match word {
+            Some((len, word, syllables)) => {
+                ret.push(Annotation {
+                    text: word.to_owned(),
+                    syllables,
+                });
+                i += len;
+            }
+            None => {
+                match ret.last_mut().filter(|last| !last.is_annotated()) {
+                    Some(last) => last.text.push(chars[i].1),
+                    None => ret.push(Annotation {
+                        text: chars[i].1.to_string(),
+                        syllables: Vec::new(),
+                    }),
+                }
+                i += 1;
+            }
+        }
+    }
+    ret
+}
+
+/// Read text with a lexicon and write it in a notation
+///
+/// Words are separated by spaces, and text the lexicon doesn't know is kept
+/// as it is. Words that can't be written in the notation are kept as Hanzi.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let cedict = Cedict::parse(
+///     "長城 长城 [Chang2 cheng2] /the Great Wall/\n\
+///      很 很 [hen3] /very/\n\
+///      長 长 [chang2] /long/\n\
+///      長 长 [zhang3] /to grow/\n",
+/// );
+/// assert_eq!(annotate_text("长城很长。", &cedict, &Pinyin), "chángchéng hěn cháng。");
+/// assert_eq!(annotate_text("长城", &cedict, &Zhuyin), "ㄔㄤˊㄔㄥˊ");
+/// ```
+pub fn annotate_text<L, N>(text: &str, lexicon: &L, notation: &N) -> String
+where
+    L: Lexicon + ?Sized,
+    N: Notation + ?Sized,
+{
+    let mut ret = String::new();
+    let mut after_word = false;
+    for annotation in annotate(text, lexicon) {
+        let (text, starts_word, ends_word) = match annotation.render(notation) {
+            Some(rendered) => (rendered, true, true),
+            None => {
+                let starts_word = annotation.text.starts_with(char::is_alphanumeric);
+                let ends_word = annotation.text.ends_with(char::is_alphanumeric);
+                (annotation.text, starts_word, ends_word)
+            }
+        };
+        if after_word && starts_word {
+            ret.push(' ');
+        }
+        ret.push_str(&text);
+        after_word = ends_word;
+    }
+    ret
+}
@@ -6,9 +6,70 @@ extern crate phf;
 use std::str;
 use std::string::String;
 
-// MAP_P2Z and MAP_Z2P static maps
+// MAP_P2Z and MAP_Z2P static maps, and the SYLLABLES table
 include!(concat!(env!("OUT_DIR"), "/codegen.rs"));
 
+mod cedict;
+mod complete;
+mod composer;
+mod detect;
+mod efeo;
+mod fuzzy;
+mod hangul;
+#[cfg(feature = "hanzi")]
+mod hanzi;
+mod ime_table;
+mod json;
+mod katakana;
+mod keyboard;
+mod latinxua;
+mod lexicon;
+mod mainland_braille;
+mod moedict;
+mod notation;
+mod palladius;
+mod reverse_index;
+mod rime;
+mod segment;
+mod shuangpin;
+mod taiwan_braille;
+mod tongyong;
+mod unihan;
+mod user_dict;
+mod wade_giles;
+mod xcompose;
+mod yale;
+
+pub use cedict::*;
+pub use complete::*;
+pub use composer::*;
+pub use detect::*;
+pub use efeo::*;
+pub use fuzzy::*;
+pub use hangul::*;
+#[cfg(feature = "hanzi")]
+pub use hanzi::*;
+pub use ime_table::*;
+pub use katakana::*;
+pub use keyboard::*;
+pub use latinxua::*;
+pub use lexicon::*;
+pub use mainland_braille::*;
+pub use moedict::*;
+pub use notation::*;
+pub use palladius::*;
+pub use reverse_index::*;
+pub use rime::*;
+pub use segment::*;
+pub use shuangpin::*;
+pub use taiwan_braille::*;
+pub use tongyong::*;
+pub use unihan::*;
+pub use user_dict::*;
+pub use wade_giles::*;
+pub use xcompose::*;
+pub use yale::*;
+
 static PINYIN_TONES: [[char; 5]; 6] = [
     ['ā', 'á', 'ǎ', 'à', 'a'],
     ['ō', 'ó', 'ǒ', 'ò', 'o'],
@@ -112,6 +173,97 @@ fn decode_rhyme(s: &str) -> Option<(String, u8)> {
     Some((rhyme, tone))
 }
 
+/// Tone marks written after a letter that has no precomposed form
+static COMBINING_TONES: [char; 4] = ['\u{304}', '\u{301}', '\u{30c}', '\u{300}'];
+
+/// Returns the byte offset of the vowel that carries the tone mark of a
+/// romanized syllable: `a`, else `e` or `o`, else the last `i` or `u`
+fn tone_vowel(s: &str) -> Option<usize> {
+    s.find('a')
+        .or_else(|| s.find(['e', 'o']))
+        .or_else(|| s.rfind(['i', 'u']))
+}
+
+/// Put a pinyin tone mark on a romanized syllable, such as `hwài`
+///
+/// A syllable without a vowel, such as Yale `shr`, gets a combining mark after
+/// its last letter. The 5th tone is left unmarked.
+fn mark_tone(s: &str, tone: u8) -> String {
+    if tone == 5 {
+        return s.to_owned();
+    }
+    match tone_vowel(s) {
+        Some(i) => {
+            let mut ret = String::with_capacity(s.len() + 2);
+            ret.push_str(&s[..i]);
+            ret.push(get_tonal_mark(s.as_bytes()[i], tone));
+            ret.push_str(&s[i + 1..]);
+            ret
+        }
+        None => format!("{}{}", s, COMBINING_TONES[tone as usize - 1]),
+    }
+}
+
+/// Remove the pinyin tone mark of a romanized syllable, precomposed or
+/// combining
+///
+/// Returns the syllable and its tone, the 5th one if unmarked, or None if it
+/// has more than one mark.
+fn unmark_tone(s: &str) -> Option<(String, u8)> {
+    let mut ret = String::with_capacity(s.len());
+    let mut tone = 5;
+    for c in s.chars() {
+        let (c, mark) = match c {
+            'ḿ' => (Some('m'), 2),
+            'ń' => (Some('n'), 2),
+            'ň' => (Some('n'), 3),
+            'ǹ' => (Some('n'), 4),
+            'ŕ' => (Some('r'), 2),
+            'ź' => (Some('z'), 2),
+            _ => match COMBINING_TONES.iter().position(|&mark| mark == c) {
+                Some(i) => (None, i as u8 + 1),
+                None => {
+                    let (rhyme, mark) = decode_rhyme(c.encode_utf8(&mut [0; 4]))?;
+                    (rhyme.chars().next(), mark)
+                }
+            },
+        };
+        if mark != 5 {
+            if tone != 5 {
+                return None;
+            }
+            tone = mark;
+        }
+        ret.extend(c);
+    }
+    Some((ret, tone))
+}
+
+/// Returns true if a rhyme is a syllabic nasal, written without a vowel
+fn is_nasal(rhyme: &str) -> bool {
+    matches!(rhyme, "m" | "n" | "ng")
+}
+
+/// Write a syllabic nasal in pinyin, such as `ńg`, with the mark on its first
+/// letter
+fn encode_nasal(rhyme: &str, tone: u8) -> String {
+    let (first, rest) = rhyme.split_at(1);
+    match (first, tone) {
+        ("m", 2) => format!("ḿ{}", rest),
+        ("n", 2) => format!("ń{}", rest),
+        ("n", 3) => format!("ň{}", rest),
+        ("n", 4) => format!("ǹ{}", rest),
+        _ => mark_tone(first, tone) + rest,
+    }
+}
+
+/// Read a syllabic nasal in pinyin, such as `ńg`
+///
+/// Returns the rhyme and the tone, or None if it isn't one.
+fn decode_nasal(s: &str) -> Option<(String, u8)> {
+    unmark_tone(s).filter(|(rhyme, _)| is_nasal(rhyme))
+}
+
 /// Split numbered pinyin to (consonant, rhyme, tone)
 ///
 /// Returns None on a missing tone or invalid input.
@@ -156,6 +308,12 @@ fn _split(s: &str) -> Option<(&str, &str, u8)> {
     }
     let rhyme = &s[consonant.len()..pos];
 
+    // Syllabic nasals are all rhyme
+    let (consonant, rhyme) = match consonant {
+        "m" | "n" | "ng" if rhyme.is_empty() => ("", consonant),
+        _ => (consonant, rhyme),
+    };
+
     // Check for invalid inputs:
     // 1) No rhyme
     // 2) More than one character for the tone
@@ -164,7 +322,7 @@ fn _split(s: &str) -> Option<(&str, &str, u8)> {
     }
 
     // Last byte is the tone
-    let tone = s.chars().rev().next().unwrap().to_digit(10)? as u8;
+    let tone = s.chars().next_back().unwrap().to_digit(10)? as u8;
 
     // Tone can only be 1-5
     if !(1..=5).contains(&tone) {
@@ -174,6 +332,108 @@ fn _split(s: &str) -> Option<(&str, &str, u8)> {
     Some((consonant, rhyme, tone))
 }
 
+/// Iterate over every valid syllable, spelled as numbered pinyin without the tone
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert!(syllables().any(|s| s == "zhuang"));
+/// assert!(syllables().all(|s| encode_pinyin(format!("{}1", s)).is_some()));
+/// ```
+pub fn syllables() -> impl Iterator<Item = &'static str> {
+    SYLLABLES.keys().copied()
+}
+
+/// Look up numbered pinyin in the syllable table
+///
+/// Returns the toneless syllable, its initial, its final and the tone.
+fn lookup_syllable(s: &str) -> Option<(&'static str, &'static str, &'static str, u8)> {
+    let (consonant, rhyme, tone) = _split(s)?;
+
+    // The table spells 'ü' as 'v' after 'j', 'q', 'x' and 'y' too
+    let mut syllable = String::with_capacity(consonant.len() + rhyme.len());
+    syllable.push_str(consonant);
+    match consonant {
+        "j" | "q" | "x" | "y" if rhyme.as_bytes()[0] == b'u' => {
+            syllable.push('v');
+            syllable.push_str(&rhyme[1..]);
+        }
+        _ => syllable.push_str(rhyme),
+    }
+
+    let (syllable, (initial, final_)) = SYLLABLES.get_entry(&*syllable)?;
+    Some((syllable, initial, final_, tone))
+}
+
+/// Returns a syllable of the table as it's typed, with `u` for `ü` after `j`,
+/// `q`, `x` and `y`
+fn typed_syllable(syllable: &str) -> String {
+    match syllable.as_bytes()[0] {
+        b'j' | b'q' | b'x' | b'y' => syllable.replace('v', "u"),
+        _ => syllable.to_owned(),
+    }
+}
+
+/// Split numbered pinyin syllables written together, such as "zhong1guo2"
+///
+/// Returns None if the last syllable has no tone.
+fn split_numbered_word(word: &str) -> Option<Vec<&str>> {
+    let mut ret = Vec::new();
+    let mut start = 0;
+    for (i, c) in word.char_indices() {
+        if c.is_ascii_digit() {
+            ret.push(&word[start..=i]);
+            start = i + 1;
+        }
+    }
+
+    if ret.is_empty() || start != word.len() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Split zhuyin syllables written together, such as "ㄓㄨㄥㄍㄨㄛˊ"
+///
+/// Takes the longest valid syllable each time. Returns None if invalid input.
+fn split_zhuyin_word(word: &str) -> Option<Vec<&str>> {
+    let mut ret = Vec::new();
+    let mut rest = word;
+    while !rest.is_empty() {
+        // Syllable ends before a tone mark, at most three symbols in
+        let ends: Vec<usize> = rest
+            .char_indices()
+            .skip(1)
+            .map(|(i, _)| i)
+            .chain([rest.len()])
+            .take_while(|&end| !rest[..end].ends_with(is_zhuyin_tone))
+            .take(3)
+            .collect();
+        let len = ends
+            .iter()
+            .rev()
+            .map(|&end| {
+                // Keep a following tone mark with the syllable
+                match rest[end..].chars().next() {
+                    Some(c) if is_zhuyin_tone(c) => end + c.len_utf8(),
+                    _ => end,
+                }
+            })
+            .find(|&end| decode_zhuyin(&rest[..end]).is_some())?;
+        ret.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+fn is_zhuyin_tone(c: char) -> bool {
+    matches!(c, 'ˊ' | 'ˇ' | 'ˋ' | '˙')
+}
+
 /// Encode pinyin
 ///
 /// Returns None on a missing tone or invalid input.
@@ -192,15 +452,10 @@ where
     S: AsRef<str>,
 {
     let s = s.as_ref();
-
-    if s == "e5" {
-        return Some("ê".to_owned());
-    } else if s == "r" || s == "r5" {
+    if s == "r" {
         return Some("r".to_owned());
     }
-
-    let (consonant, rhyme, tone) = _split(s)?;
-    encode_pinyin_from_parts(consonant.to_owned(), rhyme.to_owned(), tone)
+    convert(s, &NumberedPinyin, &Pinyin)
 }
 
 fn encode_pinyin_from_parts(consonant: String, rhyme: String, tone: u8) -> Option<String> {
@@ -256,21 +511,7 @@ pub fn decode_pinyin<S>(s: S) -> Option<String>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
-
-    if s == "ê" {
-        return Some("e5".to_owned());
-    } else if s == "r" {
-        return Some("r5".to_owned());
-    }
-
-    let (consonant, rhyme, tone) = decode_pinyin_to_parts(s)?;
-
-    let mut ret = String::with_capacity(consonant.len() + rhyme.len() + 1);
-    ret.push_str(consonant);
-    ret.push_str(&rhyme);
-    ret.push((tone + b'0') as char);
-    Some(ret)
+    convert(s.as_ref(), &Pinyin, &NumberedPinyin)
 }
 
 fn decode_pinyin_to_parts(s: &str) -> Option<(&str, String, u8)> {
@@ -325,15 +566,7 @@ pub fn encode_zhuyin<S>(s: S) -> Option<String>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
-    if s == "e5" {
-        return Some("ㄝ".to_owned());
-    } else if s == "r5" {
-        return Some("ㄦ˙".to_owned());
-    }
-
-    let (consonant, rhyme, tone) = _split(s)?;
-    encode_zhuyin_from_parts(consonant.to_owned(), rhyme.to_owned(), tone)
+    convert(s.as_ref(), &NumberedPinyin, &Zhuyin)
 }
 
 fn encode_zhuyin_from_parts(consonant: String, rhyme: String, tone: u8) -> Option<String> {
@@ -368,7 +601,6 @@ fn encode_zhuyin_from_parts(consonant: String, rhyme: String, tone: u8) -> Optio
     } else if (consonant.as_bytes() == b"w" && rhyme.as_bytes() == b"u")
         || (consonant.as_bytes() == b"y"
             && (rhyme.as_bytes() == b"v"
-                || rhyme.as_bytes() == b"e"
                 || rhyme.as_bytes() == b"ve"
                 || rhyme.as_bytes() == b"in"
                 || rhyme.as_bytes() == b"van"
@@ -376,6 +608,12 @@ fn encode_zhuyin_from_parts(consonant: String, rhyme: String, tone: u8) -> Optio
                 || rhyme.as_bytes() == b"vn"))
     {
         consonant.clear();
+    } else if consonant.as_bytes() == b"y"
+        && (rhyme.as_bytes() == b"e" || rhyme.as_bytes() == b"ong")
+    {
+        // "ye" and "yong" are written with the finals ㄧㄝ and ㄩㄥ
+        consonant.clear();
+        rhyme.insert(0, 'i');
     }
 
     // Convert consonant
@@ -423,22 +661,7 @@ pub fn decode_zhuyin<S>(s: S) -> Option<String>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
-    if s == "ㄝ" {
-        return Some("e5".to_owned());
-    } else if s == "ㄦ˙" {
-        return Some("r5".to_owned());
-    }
-
-    let (consonant, rhyme, tone) = decode_zhuyin_to_parts(s)?;
-    if rhyme.is_empty() {
-        return None;
-    }
-    let mut ret = String::with_capacity(consonant.len() + rhyme.len() + 1);
-    ret.push_str(&consonant);
-    ret.push_str(&rhyme);
-    ret.push((tone + b'0') as char);
-    Some(ret)
+    convert(s.as_ref(), &Zhuyin, &NumberedPinyin)
 }
 
 fn decode_zhuyin_to_parts(s: &str) -> Option<(String, String, u8)> {
@@ -486,11 +709,11 @@ fn decode_zhuyin_to_parts(s: &str) -> Option<(String, String, u8)> {
         _ => return None,
     };
 
-    // Handle yi, wu, yv whole syllables 整體認讀, and the special case "ong" to "weng"
+    // Handle yi, wu, yv whole syllables 整體認讀, the rhymes spelled out after y and w,
+    // and the special case "ong" to "weng"
     if consonant.is_empty() {
         if rhyme == "i"
             || rhyme == "v"
-            || rhyme == "e"
             || rhyme == "ve"
             || rhyme == "in"
             || rhyme == "van"
@@ -502,6 +725,16 @@ fn decode_zhuyin_to_parts(s: &str) -> Option<(String, String, u8)> {
         } else if rhyme == "u" {
             consonant.clear();
             consonant.push('w');
+        } else if rhyme == "iu" {
+            consonant.clear();
+            consonant.push('y');
+            rhyme.clear();
+            rhyme.push_str("ou");
+        } else if rhyme == "ui" || rhyme == "un" {
+            // "wei" and "wen" spell out the rhyme's 'e'
+            consonant.clear();
+            consonant.push('w');
+            rhyme.replace_range(0..1, "e");
         } else if rhyme.as_bytes()[0] == b'u' {
             consonant.clear();
             consonant.push('w');
@@ -532,11 +765,7 @@ pub fn pinyin_to_zhuyin<S>(s: S) -> Option<String>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
-    if s == "ê" {
-        return Some("ㄝ".to_owned());
-    }
-    encode_zhuyin(decode_pinyin(s)?)
+    convert(s.as_ref(), &Pinyin, &Zhuyin)
 }
 
 /// Convert zhuyin to pinyin
@@ -550,11 +779,7 @@ pub fn zhuyin_to_pinyin<S>(s: S) -> Option<String>
 where
     S: AsRef<str>,
 {
-    let s = s.as_ref();
-    if s == "ㄝ" {
-        return Some("ê".to_owned());
-    }
-    encode_pinyin(decode_zhuyin(s)?)
+    convert(s.as_ref(), &Zhuyin, &Pinyin)
 }
 
 /// Replace a string's first letter without reallocating
@@ -627,6 +852,14 @@ mod tests {
         assert_eq!(encode_zhuyin("yve3"), s!("ㄩㄝˇ"));
         assert_eq!(encode_zhuyin("yue4"), s!("ㄩㄝˋ"));
         assert_eq!(encode_zhuyin("zhi4"), s!("ㄓˋ"));
+        assert_eq!(encode_zhuyin("ye4"), s!("ㄧㄝˋ"));
+        assert_eq!(encode_zhuyin("yong3"), s!("ㄩㄥˇ"));
+        assert_eq!(encode_zhuyin("you3"), s!("ㄧㄡˇ"));
+        assert_eq!(encode_zhuyin("e4"), s!("ㄜˋ"));
+        assert_eq!(encode_zhuyin("ye1"), s!("ㄧㄝ"));
+        assert_eq!(encode_zhuyin("yong1"), s!("ㄩㄥ"));
+        assert_eq!(encode_zhuyin("wei5"), s!("ㄨㄟ˙"));
+        assert_eq!(encode_zhuyin("wen3"), s!("ㄨㄣˇ"));
 
         assert_eq!(encode_zhuyin("a"), None);
         assert_eq!(encode_zhuyin("a0"), None);
@@ -689,6 +922,15 @@ mod tests {
         assert_eq!(decode_zhuyin("ㄦ˙"), s!("r5"));
         // Zhuyin-specific
         assert_eq!(decode_zhuyin("ㄨㄥˊ"), s!("weng2"));
+        assert_eq!(decode_zhuyin("ㄧㄡˇ"), s!("you3"));
+        assert_eq!(decode_zhuyin("ㄨㄟˋ"), s!("wei4"));
+        assert_eq!(decode_zhuyin("ㄨㄣˊ"), s!("wen2"));
+        assert_eq!(decode_zhuyin("ㄧㄝˋ"), s!("ye4"));
+        assert_eq!(decode_zhuyin("ㄩㄥˇ"), s!("yong3"));
+        assert_eq!(decode_zhuyin("ㄜˋ"), s!("e4"));
+        assert_eq!(decode_zhuyin("ㄜˊ"), s!("e2"));
+        assert_eq!(decode_zhuyin("ㄧㄝ"), s!("ye1"));
+        assert_eq!(decode_zhuyin("ㄨㄟ˙"), s!("wei5"));
 
         assert_eq!(decode_zhuyin("a5"), None);
         assert_eq!(decode_zhuyin("ㄩㄝㄝ"), None);
@@ -708,6 +950,14 @@ mod tests {
         assert_eq!(pinyin_to_zhuyin("wéng"), s!("ㄨㄥˊ"));
         assert_eq!(pinyin_to_zhuyin("ér"), s!("ㄦˊ"));
         assert_eq!(pinyin_to_zhuyin("r"), s!("ㄦ˙"));
+        // y and w spell out finals that zhuyin writes whole
+        assert_eq!(pinyin_to_zhuyin("yě"), s!("ㄧㄝˇ"));
+        assert_eq!(pinyin_to_zhuyin("yǒng"), s!("ㄩㄥˇ"));
+        assert_eq!(pinyin_to_zhuyin("yǒu"), s!("ㄧㄡˇ"));
+        assert_eq!(pinyin_to_zhuyin("wèi"), s!("ㄨㄟˋ"));
+        assert_eq!(pinyin_to_zhuyin("wén"), s!("ㄨㄣˊ"));
+        assert_eq!(pinyin_to_zhuyin("è"), s!("ㄜˋ"));
+        assert_eq!(pinyin_to_zhuyin("e"), s!("ㄜ˙"));
 
         assert_eq!(pinyin_to_zhuyin("wengg"), None);
         assert_eq!(pinyin_to_zhuyin("啊"), None);
@@ -721,6 +971,15 @@ mod tests {
         assert_eq!(zhuyin_to_pinyin("ㄨㄥˊ"), s!("wéng"));
         assert_eq!(zhuyin_to_pinyin("ㄦˊ"), s!("ér"));
         assert_eq!(zhuyin_to_pinyin("ㄦ˙"), s!("r"));
+        // y and w spell out finals that zhuyin writes whole
+        assert_eq!(zhuyin_to_pinyin("ㄧㄝˇ"), s!("yě"));
+        assert_eq!(zhuyin_to_pinyin("ㄩㄥˇ"), s!("yǒng"));
+        assert_eq!(zhuyin_to_pinyin("ㄧㄡˇ"), s!("yǒu"));
+        assert_eq!(zhuyin_to_pinyin("ㄨㄟˋ"), s!("wèi"));
+        assert_eq!(zhuyin_to_pinyin("ㄨㄣˊ"), s!("wén"));
+        assert_eq!(zhuyin_to_pinyin("ㄜˋ"), s!("è"));
+        assert_eq!(zhuyin_to_pinyin("ㄜ˙"), s!("e"));
+        assert_eq!(zhuyin_to_pinyin("ㄝ"), s!("ê"));
 
         assert_eq!(zhuyin_to_pinyin("ㄥㄥ"), None);
         assert_eq!(zhuyin_to_pinyin("啊"), None);
@@ -0,0 +1,353 @@
+//! Mainland Chinese Braille (現行盲文).
+//!
+//! A syllable is written as an initial cell, a final cell and an optional tone
+//! cell. Zero-initial syllables only have the final, and zhi, chi, shi, ri, zi,
+//! ci and si only have the initial. `g`/`j`, `k`/`q` and `h`/`x` share a cell
+//! and are told apart by the final.
+use crate::{lookup_syllable, split_numbered_word, Notation, Syllable, SYLLABLES};
+
+/// The blank cell written between words
+const BRAILLE_SPACE: char = '\u{2800}';
+
+static TONE_CELLS: [char; 4] = ['⠁', '⠂', '⠄', '⠆'];
+
+/// Which tones to write in mainland Braille
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMarks {
+    /// Write the tone of every syllable, except the 5th tone
+    #[default]
+    Always,
+    /// The tone-omission convention of 現行盲文
+    ///
+    /// A word of one syllable has its tone written, since it's ambiguous
+    /// alone. A word of several syllables is told apart by its spelling, and
+    /// is written without tones. The 5th tone is never written.
+    Omitted,
+    /// Never write tones
+    Never,
+}
+
+/// Mainland Braille, such as `⠌⠲⠁`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MainlandBraille;
+
+impl Notation for MainlandBraille {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_mainland_braille(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_mainland_braille(syllable.to_string())
+    }
+}
+
+/// Returns the Braille cell of an initial
+fn initial_cell(initial: &str) -> Option<char> {
+    let cell = match initial {
+        "b" => '⠃',
+        "p" => '⠏',
+        "m" => '⠍',
+        "f" => '⠋',
+        "d" => '⠙',
+        "t" => '⠞',
+        "n" => '⠝',
+        "l" => '⠇',
+        "g" | "j" => '⠛',
+        "k" | "q" => '⠅',
+        "h" | "x" => '⠓',
+        "zh" => '⠌',
+        "ch" => '⠟',
+        "sh" => '⠱',
+        "r" => '⠚',
+        "z" => '⠵',
+        "c" => '⠉',
+        "s" => '⠎',
+        _ => return None,
+    };
+    Some(cell)
+}
+
+/// Returns the Braille cell of a final
+///
+/// `io` and the syllabic nasals have no cell.
+fn final_cell(final_: &str) -> Option<char> {
+    let cell = match final_ {
+        "a" => '⠔',
+        "o" | "e" => '⠢',
+        "i" => '⠊',
+        "u" => '⠥',
+        "v" => '⠬',
+        "er" => '⠗',
+        "ai" => '⠪',
+        "ei" => '⠮',
+        "ao" => '⠖',
+        "ou" => '⠷',
+        "an" => '⠧',
+        "en" => '⠴',
+        "ang" => '⠦',
+        "eng" => '⠼',
+        "ong" | "ueng" => '⠲',
+        "ia" => '⠫',
+        "ie" => '⠑',
+        "iao" => '⠜',
+        "iu" => '⠳',
+        "ian" => '⠩',
+        "in" => '⠣',
+        "iang" => '⠭',
+        "ing" => '⠡',
+        "iong" => '⠹',
+        "ua" => '⠿',
+        "uo" => '⠕',
+        "uai" => '⠽',
+        "ui" => '⠺',
+        "uan" => '⠻',
+        "un" => '⠒',
+        "uang" => '⠶',
+        "ve" => '⠾',
+        "van" => '⠯',
+        "vn" => '⠸',
+        _ => return None,
+    };
+    Some(cell)
+}
+
+/// Returns the cells of a syllable without its tone
+fn cells(initial: &str, final_: &str) -> Option<String> {
+    let mut ret = String::with_capacity(6);
+    if let Some(cell) = initial_cell(initial) {
+        ret.push(cell);
+        // The apical vowel isn't written
+        if final_ == "i" && matches!(initial, "zh" | "ch" | "sh" | "r" | "z" | "c" | "s") {
+            return Some(ret);
+        }
+    }
+    ret.push(final_cell(final_)?);
+    Some(ret)
+}
+
+/// Encode mainland Braille
+///
+/// The 5th tone is never written. Returns None on a missing tone or invalid input,
+/// and for `yo` and the syllabic nasals, which have no cells.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_mainland_braille("zhong1"), Some("⠌⠲⠁".to_owned()));
+/// assert_eq!(encode_mainland_braille("shi4"), Some("⠱⠆".to_owned()));
+///
+/// assert_eq!(encode_mainland_braille("zhong"), None);
+/// ```
+pub fn encode_mainland_braille<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, initial, final_, tone) = lookup_syllable(s.as_ref())?;
+
+    let mut ret = cells(initial, final_)?;
+    if tone != 5 {
+        ret.push(TONE_CELLS[tone as usize - 1]);
+    }
+    Some(ret)
+}
+
+/// Encode text as mainland Braille
+///
+/// Words are separated by whitespace, and each word is numbered pinyin syllables
+/// written together. Words are separated by a blank cell in the result.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     encode_mainland_braille_text("zhong1guo2 ren2", ToneMarks::Omitted),
+///     Some("⠌⠲⠛⠕\u{2800}⠚⠴⠂".to_owned())
+/// );
+/// ```
+pub fn encode_mainland_braille_text<S>(s: S, tones: ToneMarks) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for word in s.as_ref().split_whitespace() {
+        if !ret.is_empty() {
+            ret.push(BRAILLE_SPACE);
+        }
+
+        let syllables = split_numbered_word(word)?;
+        let write_tone = match tones {
+            ToneMarks::Always => true,
+            ToneMarks::Omitted => syllables.len() == 1,
+            ToneMarks::Never => false,
+        };
+        for syllable in syllables {
+            let (_, initial, final_, tone) = lookup_syllable(syllable)?;
+            ret.push_str(&cells(initial, final_)?);
+
+            if write_tone && tone != 5 {
+                ret.push(TONE_CELLS[tone as usize - 1]);
+            }
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Decode mainland Braille to numbered pinyin
+///
+/// Accepts a single syllable or text encoded by `encode_mainland_braille_text`.
+/// Syllables without a tone cell get the 5th tone. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_mainland_braille("⠌⠲⠁"), Some("zhong1".to_owned()));
+/// assert_eq!(
+///     decode_mainland_braille("⠌⠲⠁⠛⠕\u{2800}⠚⠴⠂"),
+///     Some("zhong1guo5 ren2".to_owned())
+/// );
+/// ```
+pub fn decode_mainland_braille<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    // 'o' shares its cell with 'e', and is only read after b, p, m and f
+    let table: Vec<_> = SYLLABLES
+        .entries()
+        .filter(|(_, (initial, final_))| {
+            *final_ != "o" || matches!(*initial, "b" | "p" | "m" | "f")
+        })
+        .filter_map(|(syllable, (initial, final_))| Some((cells(initial, final_)?, *syllable)))
+        .collect();
+
+    let mut ret = String::new();
+    for word in s
+        .as_ref()
+        .split(|c: char| c == BRAILLE_SPACE || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+    {
+        if !ret.is_empty() {
+            ret.push(' ');
+        }
+
+        let mut rest = word;
+        while !rest.is_empty() {
+            // Prefer an initial with its final over an initial standing alone
+            let (cells, syllable) = table
+                .iter()
+                .filter(|(cells, _)| rest.starts_with(cells.as_str()))
+                .max_by_key(|(cells, _)| cells.len())?;
+            rest = &rest[cells.len()..];
+            ret.push_str(syllable);
+
+            let tone = rest
+                .chars()
+                .next()
+                .and_then(|c| TONE_CELLS.iter().position(|&tone| tone == c));
+            match tone {
+                Some(i) => {
+                    ret.push((b'1' + i as u8) as char);
+                    rest = &rest[TONE_CELLS[i].len_utf8()..];
+                }
+                None => ret.push('5'),
+            }
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_imports)]
+    use crate::convert;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_mainland_braille_test() {
+        assert_eq!(encode_mainland_braille("ma1"), s!("⠍⠔⠁"));
+        assert_eq!(encode_mainland_braille("bo2"), s!("⠃⠢⠂"));
+        assert_eq!(encode_mainland_braille("ge4"), s!("⠛⠢⠆"));
+        assert_eq!(encode_mainland_braille("ji3"), s!("⠛⠊⠄"));
+        assert_eq!(encode_mainland_braille("qu4"), s!("⠅⠬⠆"));
+        assert_eq!(encode_mainland_braille("xue2"), s!("⠓⠾⠂"));
+        assert_eq!(encode_mainland_braille("zi4"), s!("⠵⠆"));
+        assert_eq!(encode_mainland_braille("ri4"), s!("⠚⠆"));
+        assert_eq!(encode_mainland_braille("yi1"), s!("⠊⠁"));
+        assert_eq!(encode_mainland_braille("you3"), s!("⠳⠄"));
+        assert_eq!(encode_mainland_braille("wei4"), s!("⠺⠆"));
+        assert_eq!(encode_mainland_braille("weng1"), s!("⠲⠁"));
+        assert_eq!(encode_mainland_braille("yuan2"), s!("⠯⠂"));
+        assert_eq!(encode_mainland_braille("er4"), s!("⠗⠆"));
+        assert_eq!(encode_mainland_braille("de5"), s!("⠙⠢"));
+
+        assert_eq!(encode_mainland_braille("ma"), None);
+        assert_eq!(encode_mainland_braille("lvan4"), None);
+        assert_eq!(encode_mainland_braille(""), None);
+    }
+
+    #[test]
+    fn encode_mainland_braille_text_test() {
+        let text = "wo3men5 xue2xi2 zhong1wen2 le5 ma3";
+        assert_eq!(
+            encode_mainland_braille_text(text, ToneMarks::Always),
+            s!("⠕⠄⠍⠴\u{2800}⠓⠾⠂⠓⠊⠂\u{2800}⠌⠲⠁⠒⠂\u{2800}⠇⠢\u{2800}⠍⠔⠄")
+        );
+        assert_eq!(
+            encode_mainland_braille_text(text, ToneMarks::Omitted),
+            s!("⠕⠍⠴\u{2800}⠓⠾⠓⠊\u{2800}⠌⠲⠒\u{2800}⠇⠢\u{2800}⠍⠔⠄")
+        );
+        assert_eq!(
+            encode_mainland_braille_text(text, ToneMarks::Never),
+            s!("⠕⠍⠴\u{2800}⠓⠾⠓⠊\u{2800}⠌⠲⠒\u{2800}⠇⠢\u{2800}⠍⠔")
+        );
+
+        assert_eq!(
+            encode_mainland_braille_text("wo3men", ToneMarks::Always),
+            None
+        );
+        assert_eq!(
+            encode_mainland_braille_text("wo3 ma6", ToneMarks::Always),
+            None
+        );
+        assert_eq!(encode_mainland_braille_text(" ", ToneMarks::Always), None);
+    }
+
+    #[test]
+    fn decode_mainland_braille_test() {
+        assert_eq!(decode_mainland_braille("⠍⠔⠁"), s!("ma1"));
+        assert_eq!(decode_mainland_braille("⠃⠢⠂"), s!("bo2"));
+        assert_eq!(decode_mainland_braille("⠛⠢⠆"), s!("ge4"));
+        assert_eq!(decode_mainland_braille("⠛⠊⠄"), s!("ji3"));
+        assert_eq!(decode_mainland_braille("⠵⠆"), s!("zi4"));
+        assert_eq!(decode_mainland_braille("⠢"), s!("e5"));
+        assert_eq!(decode_mainland_braille("⠳⠄"), s!("you3"));
+        assert_eq!(
+            decode_mainland_braille("⠕⠄⠍⠴\u{2800}⠓⠾⠂⠓⠊ ⠌⠲⠁⠒"),
+            s!("wo3men5 xve2xi5 zhong1wen5")
+        );
+
+        assert_eq!(
+            convert("ㄓㄨㄥ", &crate::Zhuyin, &MainlandBraille),
+            s!("⠌⠲⠁")
+        );
+        assert_eq!(convert("⠌⠲⠁⠛⠕", &MainlandBraille, &crate::Zhuyin), None);
+
+        assert_eq!(decode_mainland_braille("⠁"), None);
+        assert_eq!(decode_mainland_braille("ma1"), None);
+        assert_eq!(decode_mainland_braille(""), None);
+    }
+}
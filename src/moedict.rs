@@ -0,0 +1,425 @@
+//! Taiwan readings from the Ministry of Education dictionary.
+//!
+//! The moedict JSON export is an array of entries, each with a `title` and
+//! its `heteronyms`, the readings of the word. Each reading has `bopomofo`,
+//! with syllables separated by spaces and the neutral tone mark written first,
+//! as in `˙ㄇㄚ`. The packed export, which names these `t`, `h` and `b`, loads
+//! the same way.
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::json::Json;
+use crate::{decode_pinyin, decode_zhuyin, Lexicon, Notation, Syllable, Zhuyin};
+
+/// Word readings, loaded from a moedict JSON export
+///
+/// Readings of a word keep the order of the file.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let moedict = Moedict::parse(
+///     r#"[{"title": "星期", "heteronyms": [{"bopomofo": "ㄒㄧㄥ　ㄑㄧˊ"}]}]"#,
+/// )
+/// .unwrap();
+/// assert_eq!(moedict.word_readings("星期"), ["xing1 qi2"]);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Moedict {
+    words: HashMap<String, Vec<Vec<Syllable>>>,
+    longest_word: usize,
+    skipped: usize,
+}
+
+/// Returns the first member of an object with one of some names
+fn member<'a>(json: &'a Json, names: &[&str]) -> Option<&'a Json> {
+    names.iter().find_map(|name| json.get(name))
+}
+
+/// Parse the zhuyin of a reading, such as `ㄌㄜˋ　ㄙㄜˋ`
+///
+/// Notes such as `（語音）` are left out, and only the first reading is read
+/// if there are several.
+fn parse_bopomofo(bopomofo: &str) -> Option<Vec<Syllable>> {
+    let mut bopomofo = bopomofo.trim_start();
+    while let Some(rest) = bopomofo.strip_prefix('（') {
+        bopomofo = rest.split_once('）')?.1.trim_start();
+    }
+    let bopomofo = bopomofo.split('（').next().unwrap_or(bopomofo);
+
+    bopomofo
+        .split_whitespace()
+        .map(|syllable| {
+            let numbered = match syllable.strip_prefix('˙') {
+                Some(rest) => decode_zhuyin(format!("{}˙", rest)),
+                None => decode_zhuyin(syllable),
+            }?;
+            Syllable::new(&numbered)
+        })
+        .collect()
+}
+
+impl Moedict {
+    /// Create an empty dictionary
+    pub fn new() -> Moedict {
+        Moedict::default()
+    }
+
+    /// Load a moedict JSON file
+    ///
+    /// Fails with `InvalidData` if the file isn't a moedict export.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Moedict> {
+        Moedict::read(File::open(path)?)
+    }
+
+    /// Load a moedict JSON export from a reader
+    ///
+    /// Fails with `InvalidData` if the input isn't a moedict export.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Moedict> {
+        let mut s = String::new();
+        reader.read_to_string(&mut s)?;
+        Moedict::parse(&s)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a moedict JSON export"))
+    }
+
+    /// Load a moedict JSON export from a string
+    ///
+    /// Returns None if the input isn't a JSON array of entries.
+    pub fn parse(s: &str) -> Option<Moedict> {
+        let mut ret = Moedict::new();
+        for entry in Json::parse(s)?.as_array()? {
+            ret.add_entry(entry);
+        }
+        Some(ret)
+    }
+
+    fn add_entry(&mut self, entry: &Json) {
+        let Some(title) = member(entry, &["title", "t"]).and_then(Json::as_str) else {
+            self.skipped += 1;
+            return;
+        };
+        let heteronyms = member(entry, &["heteronyms", "h"])
+            .and_then(Json::as_array)
+            .unwrap_or_default();
+        for heteronym in heteronyms {
+            let syllables = member(heteronym, &["bopomofo", "b"])
+                .and_then(Json::as_str)
+                .and_then(parse_bopomofo);
+            match syllables {
+                Some(syllables) if syllables.len() == title.chars().count() => {
+                    self.add(title, syllables)
+                }
+                _ => self.skipped += 1,
+            }
+        }
+    }
+
+    /// Add a reading of a word, one syllable per character
+    pub fn add(&mut self, word: &str, syllables: Vec<Syllable>) {
+        let readings = self.words.entry(word.to_owned()).or_default();
+        if !readings.contains(&syllables) {
+            readings.push(syllables);
+        }
+        self.longest_word = self.longest_word.max(word.chars().count());
+    }
+
+    /// Returns the number of words with readings
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if no word has a reading
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the number of entries and readings that couldn't be read
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns the readings of a word as numbered pinyin, syllables separated by
+    /// spaces
+    ///
+    /// Returns an empty list if the word is unknown.
+    pub fn word_readings(&self, word: &str) -> Vec<String> {
+        self.readings(word)
+            .iter()
+            .map(|reading| {
+                reading
+                    .iter()
+                    .map(Syllable::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    /// Compare the readings of every word with mainland readings
+    ///
+    /// The first reading of each word is compared with the first mainland
+    /// reading. Returns the words that are read differently, in order.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let moedict = Moedict::parse(
+    ///     r#"[{"title": "垃圾", "heteronyms": [{"bopomofo": "ㄌㄜˋ　ㄙㄜˋ"}]},
+    ///         {"title": "銀行", "heteronyms": [{"bopomofo": "ㄧㄣˊ　ㄏㄤˊ"}]}]"#,
+    /// )
+    /// .unwrap();
+    /// let cedict = Cedict::parse(
+    ///     "垃圾 垃圾 [la1 ji1] /trash/\n\
+    ///      銀行 银行 [yin2 hang2] /bank/\n",
+    /// );
+    /// let diffs = moedict.compare(&cedict);
+    /// assert_eq!(diffs.len(), 1);
+    /// assert_eq!(diffs[0].word, "垃圾");
+    /// assert_eq!(diffs[0].to_string(), "垃圾: ㄌㄜˋ ㄙㄜˋ / la1 ji1");
+    /// ```
+    pub fn compare<L: Lexicon + ?Sized>(&self, mainland: &L) -> Vec<WordDiff> {
+        let mut words: Vec<&String> = self.words.keys().collect();
+        words.sort_unstable();
+
+        words
+            .into_iter()
+            .filter_map(|word| {
+                let taiwan = self.words[word].first()?;
+                let mainland: Vec<Syllable> = mainland
+                    .readings(word)
+                    .into_iter()
+                    .find(|reading| reading.len() == taiwan.len())?
+                    .iter()
+                    .map(Syllable::normalized)
+                    .collect();
+                let diff = WordDiff {
+                    word: word.clone(),
+                    taiwan: taiwan.clone(),
+                    mainland,
+                };
+                (!diff.is_same()).then_some(diff)
+            })
+            .collect()
+    }
+}
+
+impl Lexicon for Moedict {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        self.words.get(word).cloned().unwrap_or_default()
+    }
+
+    fn longest_word(&self) -> usize {
+        self.longest_word
+    }
+
+    fn words(&self) -> Vec<&str> {
+        self.words.keys().map(String::as_str).collect()
+    }
+}
+
+/// How one syllable of a word is read differently
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SyllableDiff {
+    /// The position of the syllable in the word
+    pub index: usize,
+    /// The consonants differ
+    pub consonant: bool,
+    /// The rhymes differ
+    pub rhyme: bool,
+    /// The tones differ
+    pub tone: bool,
+}
+
+/// A Taiwan reading and a mainland reading of a word
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WordDiff {
+    /// The word
+    pub word: String,
+    /// The Taiwan reading, one syllable per character
+    pub taiwan: Vec<Syllable>,
+    /// The mainland reading, one syllable per character
+    pub mainland: Vec<Syllable>,
+}
+
+impl WordDiff {
+    /// Compare a Taiwan zhuyin reading with a mainland pinyin reading
+    ///
+    /// Both readings have syllables separated by spaces. Returns None if
+    /// invalid input, or if the readings have different numbers of syllables.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let diff = WordDiff::new("星期", "ㄒㄧㄥ ㄑㄧˊ", "xīng qī").unwrap();
+    /// let syllables = diff.differences();
+    /// assert_eq!(syllables.len(), 1);
+    /// assert_eq!(syllables[0].index, 1);
+    /// assert!(syllables[0].tone && !syllables[0].rhyme);
+    /// ```
+    pub fn new(word: &str, zhuyin: &str, pinyin: &str) -> Option<WordDiff> {
+        let taiwan: Vec<Syllable> = zhuyin
+            .split_whitespace()
+            .map(|syllable| Syllable::new(&decode_zhuyin(syllable)?))
+            .collect::<Option<_>>()?;
+        let mainland: Vec<Syllable> = pinyin
+            .split_whitespace()
+            .map(|syllable| Syllable::new(&decode_pinyin(syllable)?))
+            .collect::<Option<_>>()?;
+        if taiwan.len() != mainland.len() {
+            return None;
+        }
+
+        Some(WordDiff {
+            word: word.to_owned(),
+            taiwan,
+            mainland,
+        })
+    }
+
+    /// Returns the syllables read differently
+    pub fn differences(&self) -> Vec<SyllableDiff> {
+        self.taiwan
+            .iter()
+            .zip(&self.mainland)
+            .enumerate()
+            .filter(|(_, (taiwan, mainland))| taiwan != mainland)
+            .map(|(index, (taiwan, mainland))| SyllableDiff {
+                index,
+                consonant: taiwan.consonant != mainland.consonant,
+                rhyme: taiwan.rhyme != mainland.rhyme,
+                tone: taiwan.tone != mainland.tone,
+            })
+            .collect()
+    }
+
+    /// Returns true if both readings are the same
+    pub fn is_same(&self) -> bool {
+        self.taiwan == self.mainland
+    }
+
+    /// Returns true if the readings differ only in tones
+    pub fn tones_only(&self) -> bool {
+        self.differences()
+            .iter()
+            .all(|diff| !diff.consonant && !diff.rhyme)
+    }
+}
+
+impl fmt::Display for WordDiff {
+    /// Writes the word, the Taiwan reading in zhuyin and the mainland reading
+    /// in numbered pinyin
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let zhuyin: Vec<String> = self
+            .taiwan
+            .iter()
+            .map(|syllable| Zhuyin.render(syllable).unwrap_or_default())
+            .collect();
+        let pinyin: Vec<String> = self.mainland.iter().map(Syllable::to_string).collect();
+        write!(
+            f,
+            "{}: {} / {}",
+            self.word,
+            zhuyin.join(" "),
+            pinyin.join(" ")
+        )
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    static SAMPLE: &str = r#"[
+{"title": "垃圾", "heteronyms": [{"bopomofo": "ㄌㄜˋ　ㄙㄜˋ", "pinyin": "lè sè",
+    "definitions": [{"type": "名", "def": "穢物、廢棄物。"}]}]},
+{"title": "星期", "heteronyms": [{"bopomofo": "ㄒㄧㄥ　ㄑㄧˊ", "pinyin": "xīng qí"}]},
+{"title": "行", "heteronyms": [{"bopomofo": "ㄒㄧㄥˊ"}, {"bopomofo": "ㄏㄤˊ"},
+    {"bopomofo": "ㄒㄧㄥˊ"}]},
+{"title": "銀行", "heteronyms": [{"bopomofo": "ㄧㄣˊ　ㄏㄤˊ"}]},
+{"title": "媽媽", "heteronyms": [{"bopomofo": "ㄇㄚ　˙ㄇㄚ"}]},
+{"title": "血", "heteronyms": [{"bopomofo": "（語音）ㄒㄧㄝˇ"}, {"bopomofo": "（讀音）ㄒㄩㄝˋ"}]},
+{"t": "綠", "h": [{"b": "ㄌㄩˋ", "p": "lǜ"}]},
+{"title": "{[8e40]}", "heteronyms": [{"bopomofo": "ㄅㄚ"}]},
+{"title": "壞", "heteronyms": [{"bopomofo": "ㄏㄨㄞˋ ㄏㄨㄞˋ"}, {"pinyin": "huài"}]},
+{"heteronyms": []}
+]"#;
+
+    #[test]
+    fn moedict_test() {
+        let moedict = Moedict::read(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(Some(&moedict), Moedict::parse(SAMPLE).as_ref());
+        assert_eq!(moedict.len(), 7);
+        assert_eq!(moedict.skipped(), 4);
+        assert_eq!(moedict.longest_word(), 2);
+
+        assert_eq!(moedict.word_readings("垃圾"), ["le4 se4"]);
+        assert_eq!(moedict.word_readings("行"), ["xing2", "hang2"]);
+        assert_eq!(moedict.word_readings("媽媽"), ["ma1 ma5"]);
+        assert_eq!(moedict.word_readings("血"), ["xie3", "xve4"]);
+        assert_eq!(moedict.word_readings("綠"), ["lv4"]);
+        assert!(moedict.word_readings("壞").is_empty());
+        assert_eq!(
+            crate::annotate_text("銀行", &moedict, &crate::Zhuyin),
+            "ㄧㄣˊㄏㄤˊ"
+        );
+
+        assert!(Moedict::parse("{}").is_none());
+        assert!(Moedict::parse("[").is_none());
+        assert!(Moedict::parse(&"[".repeat(200_000)).is_none());
+        assert_eq!(
+            Moedict::read("[1,".as_bytes()).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(Moedict::open("/nonexistent/dict-revised.json").is_err());
+        assert!(Moedict::new().is_empty());
+    }
+
+    #[test]
+    fn compare_test() {
+        let moedict = Moedict::parse(SAMPLE).unwrap();
+        let cedict = crate::Cedict::parse(
+            "垃圾 垃圾 [la1 ji1] /trash/\n\
+             星期 星期 [xing1 qi1] /week/\n\
+             行 行 [xing2] /to walk/\n\
+             銀行 银行 [yin2 hang2] /bank/\n\
+             媽媽 妈妈 [ma1 ma5] /mum/\n\
+             血 血 [xue4] /blood/\n",
+        );
+        let diffs = moedict.compare(&cedict);
+        let words: Vec<&str> = diffs.iter().map(|diff| diff.word.as_str()).collect();
+        assert_eq!(words, ["垃圾", "星期", "血"]);
+
+        assert_eq!(diffs[0].to_string(), "垃圾: ㄌㄜˋ ㄙㄜˋ / la1 ji1");
+        assert_eq!(
+            diffs[0].differences(),
+            [
+                SyllableDiff {
+                    index: 0,
+                    consonant: false,
+                    rhyme: true,
+                    tone: true
+                },
+                SyllableDiff {
+                    index: 1,
+                    consonant: true,
+                    rhyme: true,
+                    tone: true
+                }
+            ]
+        );
+        assert!(!diffs[0].tones_only());
+        assert!(diffs[1].tones_only());
+        assert_eq!(diffs[2].to_string(), "血: ㄒㄧㄝˇ / xve4");
+
+        let diff = WordDiff::new("垃圾", "ㄌㄜˋ　ㄙㄜˋ", "lā jī").unwrap();
+        assert_eq!(diff, diffs[0]);
+        let diff = WordDiff::new("綠", "ㄌㄩˋ", "lǜ").unwrap();
+        assert!(diff.is_same() && diff.differences().is_empty());
+        assert_eq!(WordDiff::new("星期", "ㄒㄧㄥ", "xīng qī"), None);
+        assert_eq!(WordDiff::new("星期", "ㄒㄧㄥ ㄑㄧˊ", "xīng q"), None);
+    }
+}
@@ -0,0 +1,361 @@
+//! The `Notation` trait and the shared syllable representation.
+//!
+//! Every notation parses to and renders from a [`Syllable`], so converting
+//! between any two notations only needs each of them to implement
+//! [`Notation`].
+use std::fmt;
+
+use crate::{
+    decode_nasal, decode_pinyin_to_parts, decode_zhuyin_to_parts, encode_nasal,
+    encode_pinyin_from_parts, encode_zhuyin_from_parts, is_nasal, split, ZHUYIN_TONES,
+};
+
+/// A syllable as numbered pinyin split into (consonant, rhyme, tone)
+///
+/// Parsing always gives the spelling `decode_pinyin` gives, such as `yvan` for
+/// yuán, while rendering also accepts `yuan`.
+///
+/// ê (ㄝ) has the rhyme `ê`, apart from the neutral-tone `e` (ㄜ˙). Numbered
+/// pinyin has no ê and spells it `e5`, so both are written `e5` there and `e5`
+/// is read as ê.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Syllable {
+    /// The consonant, including `y` and `w`, or empty
+    pub consonant: String,
+    /// The rhyme
+    pub rhyme: String,
+    /// The tone, from 1 to 5
+    pub tone: u8,
+}
+
+impl Syllable {
+    /// Create a syllable from numbered pinyin
+    ///
+    /// Returns None on a missing tone or invalid input.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let syllable = Syllable::new("shuang1").unwrap();
+    /// assert_eq!(syllable.rhyme, "uang");
+    /// assert_eq!(syllable.to_string(), "shuang1");
+    /// ```
+    pub fn new(s: &str) -> Option<Syllable> {
+        if s == "e5" {
+            return Some(Syllable {
+                consonant: String::new(),
+                rhyme: "ê".to_owned(),
+                tone: 5,
+            });
+        }
+
+        let (consonant, rhyme, tone) = split(s)?;
+        Some(Syllable {
+            consonant: consonant.to_owned(),
+            rhyme: rhyme.to_owned(),
+            tone,
+        })
+    }
+
+    /// Create a syllable of the syllable table, such as `zhang`, with a tone
+    ///
+    /// Unlike [`Syllable::new`], `e` with the 5th tone is the neutral-tone `e`
+    /// rather than ê.
+    pub(crate) fn with_tone(syllable: &str, tone: u8) -> Option<Syllable> {
+        let s = format!("{}{}", syllable, tone);
+        let (consonant, rhyme, tone) = split(&s)?;
+        Some(Syllable {
+            consonant: consonant.to_owned(),
+            rhyme: rhyme.to_owned(),
+            tone,
+        })
+    }
+
+    /// Returns the initial and the final, with the y/w spelling rules undone
+    ///
+    /// Returns None if the syllable isn't in the syllable table.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// assert_eq!(Syllable::new("you3").unwrap().initial_final(), Some(("", "iu")));
+    /// assert_eq!(Syllable::new("ju4").unwrap().initial_final(), Some(("j", "v")));
+    /// ```
+    pub fn initial_final(&self) -> Option<(&'static str, &'static str)> {
+        crate::lookup_syllable(&self.to_string()).map(|(_, initial, final_, _)| (initial, final_))
+    }
+
+    /// Returns true if the syllable is in the syllable table
+    pub fn is_valid(&self) -> bool {
+        self.initial_final().is_some()
+    }
+
+    /// Returns true if the syllable can be read in a word: in the syllable
+    /// table, or the erhua `r5` that dictionaries write as its own syllable
+    pub(crate) fn is_word_syllable(&self) -> bool {
+        self.is_valid() || (self.consonant.is_empty() && self.rhyme == "r" && self.tone == 5)
+    }
+
+    /// Returns the syllable spelled the way parsing pinyin spells it, with `v`
+    /// for the `u` after `j`, `q`, `x` and `y`
+    pub(crate) fn normalized(&self) -> Syllable {
+        let mut ret = self.clone();
+        if matches!(ret.consonant.as_str(), "j" | "q" | "x" | "y") && ret.rhyme.starts_with('u') {
+            ret.rhyme.replace_range(0..1, "v");
+        }
+        ret
+    }
+}
+
+impl fmt::Display for Syllable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rhyme = match self.rhyme.as_str() {
+            "ê" => "e",
+            rhyme => rhyme,
+        };
+        write!(f, "{}{}{}", self.consonant, rhyme, self.tone)
+    }
+}
+
+/// A way of writing syllables
+///
+/// Implement this to add a notation; [`convert`] then converts between it and
+/// every other notation.
+pub trait Notation {
+    /// Parse one syllable written in this notation
+    ///
+    /// Returns None if invalid input, or if the notation can't be parsed.
+    fn parse(&self, s: &str) -> Option<Syllable>;
+
+    /// Render one syllable in this notation
+    ///
+    /// Returns None if the syllable can't be written in this notation.
+    fn render(&self, syllable: &Syllable) -> Option<String>;
+}
+
+/// Numbered pinyin, such as `zhang1`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NumberedPinyin;
+
+/// Pinyin with tone marks, such as `zhāng`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Pinyin;
+
+/// Zhuyin, such as `ㄓㄤ`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Zhuyin;
+
+impl Notation for NumberedPinyin {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(s)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        Some(syllable.to_string())
+    }
+}
+
+impl Notation for Pinyin {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        if s == "ê" {
+            return Syllable::new("e5");
+        } else if s == "r" {
+            return Syllable::new("r5");
+        } else if let Some((rhyme, tone)) = decode_nasal(s) {
+            return Some(Syllable {
+                consonant: String::new(),
+                rhyme,
+                tone,
+            });
+        }
+
+        let (consonant, rhyme, tone) = decode_pinyin_to_parts(s)?;
+        Some(Syllable {
+            consonant: consonant.to_owned(),
+            rhyme,
+            tone,
+        })
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        match (&*syllable.consonant, &*syllable.rhyme, syllable.tone) {
+            ("", "ê", 5) => Some("ê".to_owned()),
+            ("", "r", 5) => Some("r".to_owned()),
+            ("", rhyme, tone) if is_nasal(rhyme) => Some(encode_nasal(rhyme, tone)),
+            _ => encode_pinyin_from_parts(
+                syllable.consonant.clone(),
+                syllable.rhyme.clone(),
+                syllable.tone,
+            ),
+        }
+    }
+}
+
+impl Notation for Zhuyin {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        if s == "ㄝ" {
+            return Syllable::new("e5");
+        } else if s == "ㄦ˙" {
+            return Syllable::new("r5");
+        }
+
+        // Syllabic nasals are written with the initials ㄇ and ㄋ, and ㄫ
+        let mut chars = s.chars();
+        let rhyme = match chars.next() {
+            Some('ㄇ') => "m",
+            Some('ㄋ') => "n",
+            Some('ㄫ') => "ng",
+            _ => "",
+        };
+        let tone = match chars.as_str() {
+            "" => Some(1),
+            tone => ZHUYIN_TONES
+                .iter()
+                .position(|&c| tone == c.encode_utf8(&mut [0; 4]))
+                .map(|i| i as u8 + 2),
+        };
+        if let (false, Some(tone)) = (rhyme.is_empty(), tone) {
+            return Some(Syllable {
+                consonant: String::new(),
+                rhyme: rhyme.to_owned(),
+                tone,
+            });
+        }
+
+        let (consonant, rhyme, tone) = decode_zhuyin_to_parts(s)?;
+        if rhyme.is_empty() {
+            return None;
+        }
+        Some(Syllable {
+            consonant,
+            rhyme,
+            tone,
+        })
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        match (&*syllable.consonant, &*syllable.rhyme, syllable.tone) {
+            ("", "ê", 5) => Some("ㄝ".to_owned()),
+            ("", "r", 5) => Some("ㄦ˙".to_owned()),
+            ("", rhyme, tone) if is_nasal(rhyme) => {
+                let mut ret = match rhyme {
+                    "m" => "ㄇ",
+                    "n" => "ㄋ",
+                    _ => "ㄫ",
+                }
+                .to_owned();
+                if tone != 1 {
+                    ret.push(ZHUYIN_TONES[tone as usize - 2]);
+                }
+                Some(ret)
+            }
+            _ => encode_zhuyin_from_parts(
+                syllable.consonant.clone(),
+                syllable.rhyme.clone(),
+                syllable.tone,
+            ),
+        }
+    }
+}
+
+/// Convert a syllable from one notation to another
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(convert("ㄓㄤ", &Zhuyin, &Palladius), Some("чжан".to_owned()));
+/// assert_eq!(convert("mǎ", &Pinyin, &NumberedPinyin), Some("ma3".to_owned()));
+/// ```
+pub fn convert<F, T>(s: &str, from: &F, to: &T) -> Option<String>
+where
+    F: Notation + ?Sized,
+    T: Notation + ?Sized,
+{
+    to.render(&from.parse(s)?)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_imports)]
+    use crate::{Hangul, Katakana, KatakanaOptions, Palladius, TaiwanBraille};
+
+    #[test]
+    fn syllable_test() {
+        let syllable = Syllable::new("yuan2").unwrap();
+        assert_eq!(syllable.consonant, "y");
+        assert_eq!(syllable.rhyme, "uan");
+        assert_eq!(syllable.tone, 2);
+        assert_eq!(syllable.initial_final(), Some(("", "van")));
+        assert!(syllable.is_valid());
+
+        assert_eq!(Syllable::new("r5").unwrap().initial_final(), None);
+        let e = Syllable::new("e5").unwrap();
+        assert_eq!(e.rhyme, "ê");
+        assert_eq!(e.to_string(), "e5");
+        assert!(!Syllable::new("lvan4").unwrap().is_valid());
+        assert_eq!(Syllable::new("zh3"), None);
+        assert_eq!(Syllable::new(""), None);
+    }
+
+    #[test]
+    fn round_trip_test() {
+        let notations: [&dyn Notation; 4] = [&NumberedPinyin, &Pinyin, &Zhuyin, &TaiwanBraille];
+        for syllable in crate::syllables() {
+            for tone in 1..=5 {
+                let syllable = Syllable::new(&format!("{}{}", syllable, tone)).unwrap();
+                for notation in notations {
+                    // ㄫ has no Braille cell
+                    let Some(rendered) = notation.render(&syllable) else {
+                        assert_eq!(syllable.rhyme, "ng");
+                        continue;
+                    };
+                    // ㄦ˙ is read as the erhua "r5" rather than "er5"
+                    if rendered != "ㄦ˙" && rendered != "⠱⠁" {
+                        assert_eq!(notation.parse(&rendered), Some(syllable.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn convert_test() {
+        assert_eq!(
+            convert("ma3", &NumberedPinyin, &Zhuyin),
+            Some("ㄇㄚˇ".to_owned())
+        );
+        assert_eq!(convert("ㄧㄡˇ", &Zhuyin, &Pinyin), Some("yǒu".to_owned()));
+        assert_eq!(convert("wèi", &Pinyin, &Zhuyin), Some("ㄨㄟˋ".to_owned()));
+        assert_eq!(convert("ê", &Pinyin, &Zhuyin), Some("ㄝ".to_owned()));
+        assert_eq!(convert("ㄦ˙", &Zhuyin, &Pinyin), Some("r".to_owned()));
+        // The neutral-tone ㄜ˙ isn't ê
+        assert_eq!(convert("ㄜ˙", &Zhuyin, &Pinyin), Some("e".to_owned()));
+        assert_eq!(convert("ㄜ˙", &Zhuyin, &Zhuyin), Some("ㄜ˙".to_owned()));
+        assert_eq!(convert("e", &Pinyin, &Zhuyin), Some("ㄜ˙".to_owned()));
+        assert_eq!(convert("ㄝ", &Zhuyin, &Zhuyin), Some("ㄝ".to_owned()));
+        // Syllabic nasals and yo
+        assert_eq!(convert("ńg", &Pinyin, &Zhuyin), Some("ㄫˊ".to_owned()));
+        assert_eq!(convert("ㄋˋ", &Zhuyin, &Pinyin), Some("ǹ".to_owned()));
+        assert_eq!(
+            convert("m2", &NumberedPinyin, &Pinyin),
+            Some("ḿ".to_owned())
+        );
+        assert_eq!(convert("yō", &Pinyin, &Zhuyin), Some("ㄧㄛ".to_owned()));
+        assert_eq!(convert("мао", &Palladius, &Pinyin), Some("mao".to_owned()));
+        assert_eq!(convert("jīng", &Pinyin, &Hangul), Some("징".to_owned()));
+        assert_eq!(
+            convert("베이", &Hangul, &NumberedPinyin),
+            Some("bei5".to_owned())
+        );
+        assert_eq!(
+            convert("ㄒㄩㄝˊ", &Zhuyin, &Katakana(KatakanaOptions::default())),
+            Some("シュエ".to_owned())
+        );
+
+        assert_eq!(convert("シュエ", &Katakana::default(), &Pinyin), None);
+        assert_eq!(convert("ㄩㄝㄝ", &Zhuyin, &Pinyin), None);
+        assert_eq!(convert("r5", &NumberedPinyin, &Palladius), None);
+    }
+}
@@ -0,0 +1,396 @@
+//! Palladius Cyrillic transcription.
+//!
+//! Palladius writes each syllable as an initial and a final in Cyrillic, and
+//! drops the tone. Syllables of a word are written together, with `ъ` keeping a
+//! final `н` apart from a following vowel (Чанъань).
+use std::sync::OnceLock;
+
+use crate::{decode_pinyin, encode_pinyin, lookup_syllable, Notation, Syllable, SYLLABLES};
+
+/// Palladius, such as `чжан`
+///
+/// Parsing gives the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Palladius;
+
+impl Notation for Palladius {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_palladius(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_palladius(syllable.to_string())
+    }
+}
+
+/// Returns the Palladius spelling of an initial
+fn initial(initial: &str) -> &'static str {
+    match initial {
+        "b" => "б",
+        "p" => "п",
+        "m" => "м",
+        "f" => "ф",
+        "d" => "д",
+        "t" => "т",
+        "n" => "н",
+        "l" => "л",
+        "g" => "г",
+        "k" => "к",
+        "h" => "х",
+        "j" | "z" => "цз",
+        "q" | "c" => "ц",
+        "x" | "s" => "с",
+        "zh" => "чж",
+        "ch" => "ч",
+        "sh" => "ш",
+        "r" => "ж",
+        _ => "",
+    }
+}
+
+/// Returns the Palladius spelling of a final, which depends on whether there's an initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("z" | "c" | "s", "i") => "ы",
+        ("h", "ui") => "уэй",
+        ("", "io") => "йо",
+        ("", "m") => "м",
+        ("", "n") => "н",
+        ("", "ng") => "нг",
+        ("", "ua") => "ва",
+        ("", "uo") => "во",
+        ("", "uai") => "вай",
+        ("", "ui") => "вэй",
+        ("", "uan") => "вань",
+        ("", "un") => "вэнь",
+        ("", "uang") => "ван",
+        ("", "ueng") => "вэн",
+        (_, "uo") => "о",
+        (_, "a") => "а",
+        (_, "o") => "о",
+        (_, "e") => "э",
+        (_, "ai") => "ай",
+        (_, "ei") => "эй",
+        (_, "ao") => "ао",
+        (_, "ou") => "оу",
+        (_, "an") => "ань",
+        (_, "en") => "энь",
+        (_, "ang") => "ан",
+        (_, "eng") => "эн",
+        (_, "ong") => "ун",
+        (_, "er") => "эр",
+        (_, "i") => "и",
+        (_, "ia") => "я",
+        (_, "ie") => "е",
+        (_, "iao") => "яо",
+        (_, "iu") => "ю",
+        (_, "ian") => "янь",
+        (_, "in") => "инь",
+        (_, "iang") => "ян",
+        (_, "ing") => "ин",
+        (_, "iong") => "юн",
+        (_, "u") => "у",
+        (_, "ua") => "уа",
+        (_, "uai") => "уай",
+        (_, "ui") => "уй",
+        (_, "uan") => "уань",
+        (_, "un") => "унь",
+        (_, "uang") => "уан",
+        (_, "v") => "юй",
+        (_, "ve") => "юэ",
+        (_, "van") => "юань",
+        (_, "vn") => "юнь",
+        _ => unreachable!(),
+    }
+}
+
+fn is_cyrillic_vowel(c: char) -> bool {
+    "аоуыэюяеёи".contains(c)
+}
+
+/// Encode Palladius
+///
+/// Palladius has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_palladius("bei3"), Some("бэй".to_owned()));
+/// assert_eq!(encode_palladius("jing1"), Some("цзин".to_owned()));
+///
+/// assert_eq!(encode_palladius("jing"), None);
+/// ```
+pub fn encode_palladius<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, _) = lookup_syllable(s.as_ref())?;
+
+    let mut ret = String::with_capacity(12);
+    ret.push_str(initial(consonant));
+    ret.push_str(rhyme(consonant, final_));
+    Some(ret)
+}
+
+/// Decode Palladius
+///
+/// Palladius has no tones, so the result always has the 5th tone.
+/// Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_palladius("цзин"), Some("jing5".to_owned()));
+/// assert_eq!(decode_palladius("Мао"), Some("mao5".to_owned()));
+/// ```
+pub fn decode_palladius<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().to_lowercase();
+    palladius_table()
+        .iter()
+        .find(|(cyrillic, _)| *cyrillic == s)
+        .map(|(_, syllable)| format!("{}5", syllable))
+}
+
+/// Encode a word as Palladius
+///
+/// The syllables are given as numbered pinyin and written together as one word.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_palladius_word(["ze2", "dong1"]), Some("цзэдун".to_owned()));
+/// assert_eq!(encode_palladius_word(["chang2", "an1"]), Some("чанъань".to_owned()));
+/// ```
+pub fn encode_palladius_word<I, S>(syllables: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for syllable in syllables {
+        let syllable = encode_palladius(syllable)?;
+
+        // Keep a final 'н' from running into the next syllable's vowel
+        if ret.ends_with('н') && syllable.starts_with(is_cyrillic_vowel) {
+            ret.push('ъ');
+        }
+        ret.push_str(&syllable);
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Decode a Palladius word to numbered pinyin syllables
+///
+/// Every syllable has the 5th tone. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     decode_palladius_word("Бэйцзин"),
+///     Some(vec!["bei5".to_owned(), "jing5".to_owned()])
+/// );
+/// ```
+pub fn decode_palladius_word<S>(s: S) -> Option<Vec<String>>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().to_lowercase();
+
+    let mut ret = Vec::new();
+    for part in s.split('ъ') {
+        if !segment(part, 0, &mut ret, &mut vec![false; part.len()]) {
+            return None;
+        }
+    }
+    Some(ret)
+}
+
+/// Convert pinyin to Palladius
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_palladius("máo"), Some("мао".to_owned()));
+/// ```
+pub fn pinyin_to_palladius<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_palladius(decode_pinyin(s)?)
+}
+
+/// Convert Palladius to pinyin
+///
+/// Palladius has no tones, so the pinyin has no tone marks.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(palladius_to_pinyin("чжун"), Some("zhong".to_owned()));
+/// ```
+pub fn palladius_to_pinyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_pinyin(decode_palladius(s)?)
+}
+
+/// Every syllable in Palladius, paired with its numbered pinyin spelling,
+/// longest first
+fn palladius_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut ret: Vec<_> = SYLLABLES
+            .entries()
+            .map(|(syllable, (consonant, final_))| {
+                let mut cyrillic = String::from(initial(consonant));
+                cyrillic.push_str(rhyme(consonant, final_));
+                (cyrillic, *syllable)
+            })
+            .collect();
+        ret.sort_by_key(|(cyrillic, _)| std::cmp::Reverse(cyrillic.len()));
+        ret
+    })
+}
+
+/// Split a word from `pos` on into syllables, appending them to `syllables`,
+/// preferring the longest syllable at each step
+///
+/// `dead_ends` marks the positions already known not to split.
+fn segment(s: &str, pos: usize, syllables: &mut Vec<String>, dead_ends: &mut [bool]) -> bool {
+    if pos == s.len() {
+        return true;
+    } else if dead_ends[pos] {
+        return false;
+    }
+
+    for (cyrillic, syllable) in palladius_table() {
+        if !s[pos..].starts_with(cyrillic.as_str()) {
+            continue;
+        }
+        syllables.push(format!("{}5", syllable));
+        if segment(s, pos + cyrillic.len(), syllables, dead_ends) {
+            return true;
+        }
+        syllables.pop();
+    }
+
+    dead_ends[pos] = true;
+    false
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_palladius_test() {
+        assert_eq!(encode_palladius("mao2"), s!("мао"));
+        assert_eq!(encode_palladius("zhou1"), s!("чжоу"));
+        assert_eq!(encode_palladius("xi1"), s!("си"));
+        assert_eq!(encode_palladius("si1"), s!("сы"));
+        assert_eq!(encode_palladius("zi3"), s!("цзы"));
+        assert_eq!(encode_palladius("ri4"), s!("жи"));
+        assert_eq!(encode_palladius("ren2"), s!("жэнь"));
+        assert_eq!(encode_palladius("guo2"), s!("го"));
+        assert_eq!(encode_palladius("wo3"), s!("во"));
+        assert_eq!(encode_palladius("hui2"), s!("хуэй"));
+        assert_eq!(encode_palladius("gui4"), s!("гуй"));
+        assert_eq!(encode_palladius("wei4"), s!("вэй"));
+        assert_eq!(encode_palladius("sun1"), s!("сунь"));
+        assert_eq!(encode_palladius("yuan2"), s!("юань"));
+        assert_eq!(encode_palladius("yvan2"), s!("юань"));
+        assert_eq!(encode_palladius("lv4"), s!("люй"));
+        assert_eq!(encode_palladius("jue2"), s!("цзюэ"));
+        assert_eq!(encode_palladius("you3"), s!("ю"));
+        assert_eq!(encode_palladius("ye4"), s!("е"));
+        assert_eq!(encode_palladius("er4"), s!("эр"));
+
+        assert_eq!(encode_palladius("mao"), None);
+        assert_eq!(encode_palladius("fiao1"), None);
+        assert_eq!(encode_palladius("啊"), None);
+        assert_eq!(encode_palladius(""), None);
+    }
+
+    #[test]
+    fn decode_palladius_test() {
+        assert_eq!(decode_palladius("мао"), s!("mao5"));
+        assert_eq!(decode_palladius("Чжоу"), s!("zhou5"));
+        assert_eq!(decode_palladius("сы"), s!("si5"));
+        assert_eq!(decode_palladius("хуэй"), s!("hui5"));
+        assert_eq!(decode_palladius("вэй"), s!("wei5"));
+        assert_eq!(decode_palladius("юань"), s!("yvan5"));
+        assert_eq!(decode_palladius("люй"), s!("lv5"));
+
+        assert_eq!(decode_palladius("бэйцзин"), None);
+        assert_eq!(decode_palladius("mao"), None);
+        assert_eq!(decode_palladius(""), None);
+    }
+
+    #[test]
+    fn palladius_word_test() {
+        assert_eq!(
+            encode_palladius_word(["mao2", "ze2", "dong1"]),
+            s!("маоцзэдун")
+        );
+        assert_eq!(encode_palladius_word(["xi1", "an1"]), s!("сиань"));
+        assert_eq!(encode_palladius_word(["yan2", "an1"]), s!("яньань"));
+        assert_eq!(encode_palladius_word(["tian1", "jin1"]), s!("тяньцзинь"));
+        assert_eq!(encode_palladius_word(["guang3", "zhou1"]), s!("гуанчжоу"));
+        assert_eq!(encode_palladius_word(["huang2", "he2"]), s!("хуанхэ"));
+        assert_eq!(encode_palladius_word(["jing1", "yang2"]), s!("цзинъян"));
+        assert_eq!(encode_palladius_word(Vec::<&str>::new()), None);
+        assert_eq!(encode_palladius_word(["bei3", "jing"]), None);
+
+        assert_eq!(
+            decode_palladius_word("Маоцзэдун"),
+            Some(vec![
+                "mao5".to_owned(),
+                "ze5".to_owned(),
+                "dong5".to_owned()
+            ])
+        );
+        assert_eq!(
+            decode_palladius_word("Чанъань"),
+            Some(vec!["chang5".to_owned(), "an5".to_owned()])
+        );
+        assert_eq!(
+            decode_palladius_word("Сиань"),
+            Some(vec!["xi5".to_owned(), "an5".to_owned()])
+        );
+        assert_eq!(decode_palladius_word("Пекин"), None);
+        // ао is both ao and a o, so a long word has countless splits to rule out
+        assert_eq!(decode_palladius_word("ао".repeat(40) + "щ"), None);
+        assert_eq!(
+            decode_palladius_word("ао".repeat(40)).map(|w| w.len()),
+            Some(40)
+        );
+    }
+
+    #[test]
+    fn palladius_pinyin_test() {
+        assert_eq!(pinyin_to_palladius("zhōng"), s!("чжун"));
+        assert_eq!(pinyin_to_palladius("lǚ"), s!("люй"));
+        assert_eq!(palladius_to_pinyin("люй"), s!("lü"));
+        assert_eq!(palladius_to_pinyin("юань"), s!("yuan"));
+
+        assert_eq!(pinyin_to_palladius("ér"), s!("эр"));
+        assert_eq!(pinyin_to_palladius("zhāāng"), None);
+        assert_eq!(palladius_to_pinyin("啊"), None);
+    }
+}
@@ -0,0 +1,509 @@
+//! Looking up Hanzi words by their readings.
+//!
+//! A [`ReverseIndex`] maps the syllables of words, without tones, to the
+//! words. The input can be zhuyin or pinyin, with tones on every syllable, on
+//! some or on none, and pinyin may be typed without separators. Tones that are
+//! given must match.
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    decode_zhuyin, is_zhuyin_tone, split_zhuyin_word, Edge, FuzzyRules, Lattice, Lexicon, Syllable,
+};
+
+/// How loosely input matches readings
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LookupOptions {
+    /// Initials and finals that count as the same
+    pub fuzzy: FuzzyRules,
+    /// Read initials typed alone, such as `zg` for zhongguo, in pinyin
+    pub abbreviations: bool,
+}
+
+/// A word found by its reading
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Candidate {
+    /// The word
+    pub word: String,
+    /// The reading of the word that matched, one syllable per character
+    pub syllables: Vec<Syllable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    word: String,
+    syllables: Vec<Syllable>,
+    /// 0 for the first reading of the word, 1 for the second, ...
+    reading: usize,
+    weight: u32,
+}
+
+/// The most ways of reading pinyin that are looked up
+const MAX_PATHS: usize = 32;
+
+/// One syllable of the input, and what it can be read as
+struct Slot {
+    /// The syllables it can be read as, and if only fuzzily
+    keys: HashMap<String, bool>,
+    /// The tone, if one was typed
+    tone: Option<u8>,
+    abbreviated: bool,
+}
+
+/// Returns a syllable without its tone, as the syllable table spells it
+fn key(syllable: &Syllable) -> String {
+    let syllable = syllable.normalized();
+    format!("{}{}", syllable.consonant, syllable.rhyme)
+}
+
+/// Returns what some syllables can be read as, and if only fuzzily
+fn keys(syllables: &[Syllable], fuzzy: FuzzyRules) -> HashMap<String, bool> {
+    let mut ret = HashMap::new();
+    for syllable in syllables {
+        ret.insert(key(syllable), false);
+    }
+    for syllable in syllables {
+        for other in fuzzy.equivalents(syllable).iter().skip(1) {
+            ret.entry(key(other)).or_insert(true);
+        }
+    }
+    ret
+}
+
+/// Returns the first letters of some keys
+fn letters(keys: &HashMap<String, bool>) -> Vec<char> {
+    let mut ret: Vec<char> = keys.keys().filter_map(|key| key.chars().next()).collect();
+    ret.sort_unstable();
+    ret.dedup();
+    ret
+}
+
+/// Read zhuyin, where syllables without a tone mark have any tone
+fn zhuyin_slots(input: &str, fuzzy: FuzzyRules) -> Option<Vec<Slot>> {
+    let mut ret = Vec::new();
+    for word in input.split_whitespace() {
+        for zhuyin in split_zhuyin_word(word)? {
+            let syllable = Syllable::new(&decode_zhuyin(zhuyin)?)?;
+            ret.push(Slot {
+                tone: zhuyin.ends_with(is_zhuyin_tone).then_some(syllable.tone),
+                keys: keys(&[syllable], fuzzy),
+                abbreviated: false,
+            });
+        }
+    }
+    Some(ret)
+}
+
+/// Hanzi words by their readings, for finding candidates from typed input
+///
+/// Candidates are ranked by how closely they match: fewer abbreviated and
+/// fuzzy syllables first, then fewer syllables, then the first reading of a
+/// word before its other readings, then higher weights, then the order words
+/// were added in.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let cedict = Cedict::parse(
+///     "中國 中国 [Zhong1 guo2] /China/\n\
+///      種 种 [zhong3] /kind/\n\
+///      果 果 [guo3] /fruit/\n",
+/// );
+/// let index = ReverseIndex::from_lexicon(&cedict);
+/// let words = |input| -> Vec<String> {
+///     index
+///         .lookup(input, LookupOptions::default())
+///         .into_iter()
+///         .map(|candidate| candidate.word)
+///         .collect()
+/// };
+/// assert_eq!(words("zhong1guo2"), ["中国", "中國"]);
+/// assert_eq!(words("ㄓㄨㄥ ㄍㄨㄛˊ"), ["中国", "中國"]);
+/// assert_eq!(words("zhongguo"), ["中国", "中國"]);
+/// assert!(words("zhong3guo").is_empty());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReverseIndex {
+    entries: Vec<Entry>,
+    /// Entries by their syllables, such as `zhong guo`
+    sequences: HashMap<String, Vec<usize>>,
+    /// Entries by the first letters of their syllables, such as `zg`
+    initials: HashMap<String, Vec<usize>>,
+    /// Every start of the first letters of entries, such as `z` and `zg`
+    prefixes: HashSet<String>,
+    /// The number of readings of each word
+    readings: HashMap<String, usize>,
+}
+
+impl ReverseIndex {
+    /// Create an empty index
+    pub fn new() -> ReverseIndex {
+        ReverseIndex::default()
+    }
+
+    /// Index every reading of every word of a lexicon, with no weights
+    pub fn from_lexicon<L: Lexicon + ?Sized>(lexicon: &L) -> ReverseIndex {
+        let mut words = lexicon.words();
+        words.sort_unstable();
+
+        let mut ret = ReverseIndex::new();
+        for word in words {
+            for syllables in lexicon.readings(word) {
+                ret.add(word, syllables, 0);
+            }
+        }
+        ret
+    }
+
+    /// Add a reading of a word, one syllable per character
+    ///
+    /// Readings of a word added later rank after earlier ones. Among words
+    /// that match the same way, higher weights rank first, so frequencies can
+    /// be used as weights.
+    pub fn add(&mut self, word: &str, syllables: Vec<Syllable>, weight: u32) {
+        if syllables.is_empty() {
+            return;
+        }
+        let syllables: Vec<Syllable> = syllables.iter().map(Syllable::normalized).collect();
+        let keys: Vec<String> = syllables.iter().map(key).collect();
+        let sequence = keys.join(" ");
+        let entries = self.sequences.entry(sequence).or_default();
+        let exists = entries.iter().any(|&i| {
+            let entry = &self.entries[i];
+            entry.word == word && entry.syllables == syllables
+        });
+        if exists {
+            return;
+        }
+
+        let i = self.entries.len();
+        entries.push(i);
+        let initials: String = keys.iter().filter_map(|key| key.chars().next()).collect();
+        for (end, _) in initials.char_indices().skip(1) {
+            self.prefixes.insert(initials[..end].to_owned());
+        }
+        self.prefixes.insert(initials.clone());
+        self.initials.entry(initials).or_default().push(i);
+
+        let reading = self.readings.entry(word.to_owned()).or_default();
+        self.entries.push(Entry {
+            word: word.to_owned(),
+            syllables,
+            reading: *reading,
+            weight,
+        });
+        *reading += 1;
+    }
+
+    /// Returns the number of readings
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no readings
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the words some zhuyin or pinyin can be read as, best first
+    ///
+    /// Each word is returned once, with the reading that matched best. Pinyin
+    /// is only read the ways whose first letters start some word of the
+    /// index, and at most 32 of them, the fewest abbreviations and syllables
+    /// first. Returns an empty list if nothing matches or the input can't be
+    /// read.
+    ///
+    /// # Example
+    /// ```
+    /// # use pinyin_zhuyin::*;
+    /// let mut index = ReverseIndex::new();
+    /// index.add("北京", vec![Syllable::new("bei3").unwrap(), Syllable::new("jing1").unwrap()], 0);
+    /// let options = LookupOptions {
+    ///     fuzzy: FuzzyRules { in_ing: true, ..Default::default() },
+    ///     abbreviations: true,
+    /// };
+    /// assert_eq!(index.lookup("bj", options)[0].word, "北京");
+    /// assert_eq!(index.lookup("beijin", options)[0].word, "北京");
+    /// assert!(index.lookup("beijin", LookupOptions::default()).is_empty());
+    /// ```
+    pub fn lookup(&self, input: &str, options: LookupOptions) -> Vec<Candidate> {
+        let paths = match input.contains(|c| ('ㄅ'..='ㄩ').contains(&c)) {
+            true => zhuyin_slots(input, options.fuzzy).into_iter().collect(),
+            false => self.pinyin_slots(input, options),
+        };
+
+        // The best score and entry of each word
+        let mut best: HashMap<&str, (_, usize)> = HashMap::new();
+        for (path_index, path) in paths.iter().enumerate() {
+            if path.is_empty() {
+                continue;
+            }
+
+            let abbreviated = path.iter().filter(|slot| slot.abbreviated).count();
+            let letters: Vec<Vec<char>> = path.iter().map(|slot| letters(&slot.keys)).collect();
+            let lookups = self
+                .spell_initials(&letters)
+                .into_iter()
+                .filter_map(|initials| self.initials.get(&initials));
+
+            for &i in lookups.flatten() {
+                let entry = &self.entries[i];
+                if entry.syllables.len() != path.len() {
+                    continue;
+                }
+                let mut fuzzy = 0;
+                let matches = entry.syllables.iter().zip(path).all(|(syllable, slot)| {
+                    let tone = slot.tone.is_none_or(|tone| tone == syllable.tone);
+                    match slot.keys.get(&key(syllable)) {
+                        Some(&only_fuzzy) if tone => {
+                            fuzzy += only_fuzzy as usize;
+                            true
+                        }
+                        _ => false,
+                    }
+                });
+                if !matches {
+                    continue;
+                }
+
+                let score = (
+                    abbreviated,
+                    fuzzy,
+                    path_index,
+                    entry.reading,
+                    Reverse(entry.weight),
+                    i,
+                );
+                let best = best.entry(&entry.word).or_insert((score, i));
+                if score < best.0 {
+                    *best = (score, i);
+                }
+            }
+        }
+
+        let mut ranked: Vec<_> = best.into_values().collect();
+        ranked.sort_unstable();
+        ranked
+            .into_iter()
+            .map(|(_, i)| Candidate {
+                word: self.entries[i].word.clone(),
+                syllables: self.entries[i].syllables.clone(),
+            })
+            .collect()
+    }
+
+    /// Read pinyin the best ways it can be read as words of the index, where
+    /// syllables without a tone mark or number have any tone
+    fn pinyin_slots(&self, input: &str, options: LookupOptions) -> Vec<Vec<Slot>> {
+        let input = input.to_lowercase();
+        // Tone numbers separate syllables, and keep their offsets
+        let lattice = Lattice::new(
+            &input.replace(['1', '2', '3', '4', '5'], " "),
+            options.abbreviations,
+        );
+
+        // The first letters of each edge, which only words with the same
+        // first letters can match
+        let mut edge_letters = HashMap::new();
+        let mut letters_of = |edge: &Edge| -> Vec<char> {
+            edge_letters
+                .entry((edge.start, edge.end, edge.abbreviated))
+                .or_insert_with(|| letters(&keys(&edge.syllables, options.fuzzy)))
+                .clone()
+        };
+        let paths = lattice.paths_where(MAX_PATHS, |path| {
+            let letters: Vec<Vec<char>> = path.iter().map(|edge| letters_of(edge)).collect();
+            self.starts_initials(&letters)
+        });
+
+        paths
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|edge| {
+                        let number = input[edge.end..]
+                            .chars()
+                            .next()
+                            .and_then(|c| c.to_digit(10))
+                            .map(|tone| tone as u8);
+                        let mark = match edge.abbreviated {
+                            true => None,
+                            false => edge.syllables.first().map(|syllable| syllable.tone),
+                        };
+                        Slot {
+                            keys: keys(&edge.syllables, options.fuzzy),
+                            tone: number.or(mark.filter(|&tone| tone != 5)),
+                            abbreviated: edge.abbreviated,
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns every start of the first letters of entries that picking one
+    /// letter from each set spells
+    fn initials_prefixes(&self, letters: &[Vec<char>]) -> Vec<String> {
+        let mut ret = vec![String::new()];
+        for set in letters {
+            ret = ret
+                .iter()
+                .flat_map(|prefix| {
+                    set.iter().map(move |&letter| {
+                        let mut prefix = prefix.clone();
+                        prefix.push(letter);
+                        prefix
+                    })
+                })
+                .filter(|prefix| self.prefixes.contains(prefix))
+                .collect();
+        }
+        ret
+    }
+
+    /// Returns true if some entry's first letters start with letters picked
+    /// one from each set
+    fn starts_initials(&self, letters: &[Vec<char>]) -> bool {
+        !self.initials_prefixes(letters).is_empty()
+    }
+
+    /// Returns the first letters of entries that picking one letter from each
+    /// set spells
+    fn spell_initials(&self, letters: &[Vec<char>]) -> Vec<String> {
+        let mut ret = self.initials_prefixes(letters);
+        ret.retain(|initials| self.initials.contains_key(initials));
+        ret
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    static SAMPLE: &str = "\
+中國 中国 [Zhong1 guo2] /China/
+中 中 [zhong1] /middle/
+鐘 钟 [zhong1] /clock/
+行 行 [xing2] /to walk/
+行 行 [hang2] /row/
+航 航 [hang2] /to sail/
+先 先 [xian1] /first/
+西安 西安 [Xi1 an1] /Xi'an/
+北京 北京 [Bei3 jing1] /Beijing/
+女兒 女儿 [nu:3 er2] /daughter/
+學 学 [xue2] /to learn/
+媽媽 妈妈 [ma1 ma5] /mum/
+";
+
+    #[allow(dead_code)]
+    fn words(index: &ReverseIndex, input: &str, options: LookupOptions) -> Vec<String> {
+        index
+            .lookup(input, options)
+            .into_iter()
+            .map(|candidate| candidate.word)
+            .collect()
+    }
+
+    #[test]
+    fn lookup_test() {
+        let index = ReverseIndex::from_lexicon(&crate::Cedict::parse(SAMPLE));
+        assert_eq!(index.len(), 17);
+        let options = LookupOptions::default();
+
+        assert_eq!(words(&index, "zhong1guo2", options), ["中国", "中國"]);
+        assert_eq!(words(&index, "Zhōngguó", options), ["中国", "中國"]);
+        assert_eq!(words(&index, "zhong guo2", options), ["中国", "中國"]);
+        assert_eq!(words(&index, "ㄓㄨㄥㄍㄨㄛˊ", options), ["中国", "中國"]);
+        assert_eq!(words(&index, "ㄓㄨㄥ ㄍㄨㄛ", options), ["中国", "中國"]);
+        assert!(words(&index, "zhong1guo1", options).is_empty());
+        assert!(words(&index, "ㄓㄨㄥ ㄍㄨㄛˋ", options).is_empty());
+
+        // The first reading of a word ranks before the others
+        assert_eq!(words(&index, "hang", options), ["航", "行"]);
+        assert_eq!(words(&index, "xing2", options), ["行"]);
+        // Fewer syllables first
+        assert_eq!(words(&index, "xian", options), ["先", "西安"]);
+        assert_eq!(words(&index, "xi'an", options), ["西安"]);
+        assert_eq!(words(&index, "nv3er2", options), ["女儿", "女兒"]);
+        assert_eq!(words(&index, "ㄒㄩㄝˊ", options), ["学", "學"]);
+        assert_eq!(words(&index, "xue", options), ["学", "學"]);
+        assert_eq!(words(&index, "mama5", options), ["妈妈", "媽媽"]);
+        assert!(words(&index, "ma1ma1", options).is_empty());
+
+        let candidates = index.lookup("hang", options);
+        assert_eq!(candidates[1].syllables, [Syllable::new("hang2").unwrap()]);
+
+        assert!(words(&index, "", options).is_empty());
+        assert!(words(&index, "zhong6", options).is_empty());
+        assert!(words(&index, "zg", options).is_empty());
+        assert!(words(&index, "ㄓㄨㄥx", options).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_abbreviations_test() {
+        let index = ReverseIndex::from_lexicon(&crate::Cedict::parse(SAMPLE));
+        let fuzzy = LookupOptions {
+            fuzzy: FuzzyRules::all(),
+            ..Default::default()
+        };
+        assert_eq!(words(&index, "zongguo", fuzzy), ["中国", "中國"]);
+        assert_eq!(words(&index, "ㄗㄨㄥ ㄍㄨㄛˊ", fuzzy), ["中国", "中國"]);
+        assert_eq!(words(&index, "beijin", fuzzy), ["北京"]);
+        assert!(words(&index, "zongguo", LookupOptions::default()).is_empty());
+        // Exact matches rank before fuzzy ones
+        assert_eq!(words(&index, "xian1", fuzzy), ["先", "西安"]);
+        assert_eq!(words(&index, "hang", fuzzy), ["航", "行"]);
+
+        let abbreviations = LookupOptions {
+            abbreviations: true,
+            ..Default::default()
+        };
+        assert_eq!(words(&index, "zg", abbreviations), ["中国", "中國"]);
+        assert_eq!(words(&index, "zhg", abbreviations), ["中国", "中國"]);
+        assert_eq!(words(&index, "zhongg", abbreviations), ["中国", "中國"]);
+        assert_eq!(words(&index, "z1g2", abbreviations), ["中国", "中國"]);
+        assert!(words(&index, "z1g1", abbreviations).is_empty());
+        assert_eq!(words(&index, "bj", abbreviations), ["北京"]);
+        assert_eq!(words(&index, "xa", abbreviations), ["西安"]);
+        // A whole syllable ranks before abbreviated ones
+        assert_eq!(words(&index, "xian", abbreviations), ["先", "西安"]);
+
+        let mut index = ReverseIndex::new();
+        assert!(index.is_empty());
+        index.add("中", vec![Syllable::new("zhong1").unwrap()], 1);
+        index.add("钟", vec![Syllable::new("zhong1").unwrap()], 5);
+        index.add("钟", vec![Syllable::new("zhong1").unwrap()], 0);
+        index.add("空", Vec::new(), 0);
+        assert_eq!(index.len(), 2);
+        assert_eq!(words(&index, "zhong", fuzzy), ["钟", "中"]);
+    }
+
+    #[test]
+    fn long_input_test() {
+        let options = LookupOptions {
+            fuzzy: FuzzyRules::all(),
+            abbreviations: true,
+        };
+        let reading = "zhong1 hua2 ren2 min2 gong4 he2 guo2";
+        let syllables: Vec<Syllable> = reading.split(' ').filter_map(Syllable::new).collect();
+        let mut index = ReverseIndex::new();
+        index.add("中华人民共和国", syllables, 0);
+
+        // Reading the input every way fuzzy rules and abbreviations allow
+        // takes thousands of paths, most of them no word can match, so only
+        // the best few that start some word are looked up
+        let input = "zhonghuarenmingongheguo";
+        let lattice = Lattice::new(input, true);
+        assert_eq!(lattice.paths(MAX_PATHS + 1).len(), MAX_PATHS + 1);
+        assert_eq!(words(&index, input, options), ["中华人民共和国"]);
+        assert_eq!(words(&index, "zhhrmghg", options), ["中华人民共和国"]);
+        assert!(index.pinyin_slots(input, options).len() <= MAX_PATHS);
+        assert!(index.pinyin_slots(&input.repeat(4), options).is_empty());
+        assert!(words(&index, &input.repeat(4), options).is_empty());
+
+        let index = ReverseIndex::from_lexicon(&crate::Cedict::parse(SAMPLE));
+        assert!(index.pinyin_slots(input, options).len() <= MAX_PATHS);
+        assert!(words(&index, input, options).is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Rime input schema and dictionary files.
+//!
+//! The dictionary has one entry per syllable and tone, typed as pinyin with a
+//! tone number, such as `zhang1`. The schema lets the tone number be left out
+//! and syllables be abbreviated to their initials, and can show what is typed
+//! as zhuyin. Everything is generated from the syllable table and rendered
+//! with this crate, so the input method agrees with its conversions.
+use std::fmt::Write;
+
+use crate::{encode_zhuyin, typed_syllable, NotationKind, Syllable, MAP_P2Z, SYLLABLES};
+
+/// The initials, shown as zhuyin when typed alone
+static INITIALS: [&str; 21] = [
+    "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "zh", "ch", "sh", "r",
+    "z", "c", "s",
+];
+
+/// The tone marks of zhuyin, by tone number
+static ZHUYIN_TONES: [&str; 5] = ["", "ˊ", "ˇ", "ˋ", "˙"];
+
+/// Returns true if a schema id is valid: lowercase ASCII letters, digits and `_`
+fn is_schema_id(id: &str) -> bool {
+    id.starts_with(|c: char| c.is_ascii_lowercase())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Generate a Rime schema, `<id>.schema.yaml`
+///
+/// The schema uses the dictionary `<id>.dict.yaml`. If `bopomofo_preedit` is
+/// true, what is typed is shown as zhuyin. Returns None if the id isn't
+/// lowercase ASCII letters, digits and `_`, or the name is empty or has a
+/// line break.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let schema = rime_schema("syllables", "音節", true).unwrap();
+/// assert!(schema.contains("  schema_id: syllables\n"));
+/// assert!(schema.contains("    - derive/[1-5]$//\n"));
+/// assert!(schema.contains("    - xform/\\bzhang(?=[1-5]?\\b)/ㄓㄤ/\n"));
+/// ```
+pub fn rime_schema(id: &str, name: &str, bopomofo_preedit: bool) -> Option<String> {
+    if !is_schema_id(id) || name.trim().is_empty() || name.contains(['\n', '\r']) {
+        return None;
+    }
+
+    let mut ret = String::new();
+    writeln!(ret, "# Rime schema").unwrap();
+    writeln!(ret, "# encoding: utf-8").unwrap();
+    writeln!(
+        ret,
+        "# Generated by pinyin_zhuyin {}",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    writeln!(ret).unwrap();
+    writeln!(ret, "schema:").unwrap();
+    writeln!(ret, "  schema_id: {}", id).unwrap();
+    writeln!(ret, "  name: {:?}", name).unwrap();
+    writeln!(ret, "  version: {:?}", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(ret).unwrap();
+    ret.push_str(
+        "switches:
+  - name: ascii_mode
+    reset: 0
+    states: [ 中文, 西文 ]
+
+engine:
+  processors:
+    - ascii_composer
+    - recognizer
+    - key_binder
+    - speller
+    - punctuator
+    - selector
+    - navigator
+    - express_editor
+  segmentors:
+    - ascii_segmentor
+    - matcher
+    - abc_segmentor
+    - punct_segmentor
+    - fallback_segmentor
+  translators:
+    - punct_translator
+    - script_translator
+
+speller:
+  alphabet: zyxwvutsrqponmlkjihgfedcba12345
+  delimiter: \" '\"
+  algebra:
+    - derive/[1-5]$//
+    - abbrev/^([a-z]).+$/$1/
+    - abbrev/^([zcs]h).+$/$1/
+
+",
+    );
+    writeln!(ret, "translator:").unwrap();
+    writeln!(ret, "  dictionary: {}", id).unwrap();
+
+    if bopomofo_preedit {
+        writeln!(ret, "  preedit_format:").unwrap();
+        for syllable in SYLLABLES.keys() {
+            // The toneless zhuyin, as tone numbers are turned to marks below
+            let zhuyin = encode_zhuyin(format!("{}1", syllable)).unwrap();
+            writeln!(
+                ret,
+                "    - xform/\\b{}(?=[1-5]?\\b)/{}/",
+                typed_syllable(syllable),
+                zhuyin
+            )
+            .unwrap();
+        }
+        for initial in INITIALS {
+            writeln!(ret, "    - xform/\\b{}\\b/{}/", initial, MAP_P2Z[initial]).unwrap();
+        }
+        for (tone, mark) in ZHUYIN_TONES.iter().enumerate() {
+            writeln!(ret, "    - xform/([ㄅ-ㄩ]){}/$1{}/", tone + 1, mark).unwrap();
+        }
+    }
+    Some(ret)
+}
+
+/// Generate a Rime dictionary, `<id>.dict.yaml`
+///
+/// Every syllable in every tone is an entry, written in the given notation and
+/// typed as numbered pinyin. Syllables the notation can't write are left out.
+/// Returns None if the id isn't lowercase ASCII letters, digits and `_`.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let dict = rime_dict("syllables", NotationKind::Zhuyin).unwrap();
+/// assert!(dict.contains("\nㄓㄤ\tzhang1\n"));
+/// assert!(dict.contains("\nㄌㄩˋ\tlv4\n"));
+/// assert!(dict.contains("\nㄐㄩˊ\tju2\n"));
+/// ```
+pub fn rime_dict(id: &str, notation: NotationKind) -> Option<String> {
+    if !is_schema_id(id) {
+        return None;
+    }
+
+    let mut ret = String::new();
+    writeln!(ret, "# Rime dictionary").unwrap();
+    writeln!(ret, "# encoding: utf-8").unwrap();
+    writeln!(
+        ret,
+        "# Generated by pinyin_zhuyin {}",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    writeln!(ret).unwrap();
+    writeln!(ret, "---").unwrap();
+    writeln!(ret, "name: {}", id).unwrap();
+    writeln!(ret, "version: {:?}", env!("CARGO_PKG_VERSION")).unwrap();
+    writeln!(ret, "sort: original").unwrap();
+    writeln!(ret, "use_preset_vocabulary: false").unwrap();
+    writeln!(ret, "...").unwrap();
+    writeln!(ret).unwrap();
+
+    for syllable in SYLLABLES.keys() {
+        for tone in 1..=5 {
+            let numbered = format!("{}{}", syllable, tone);
+            let Some(text) =
+                Syllable::new(&numbered).and_then(|syllable| notation.notation().render(&syllable))
+            else {
+                continue;
+            };
+            writeln!(ret, "{}\t{}{}", text, typed_syllable(syllable), tone).unwrap();
+        }
+    }
+    Some(ret)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn rime_schema_test() {
+        let schema = rime_schema("pyzy_2", "Syllables", true).unwrap();
+        assert!(schema.starts_with("# Rime schema\n"));
+        assert!(schema.contains("  name: \"Syllables\"\n"));
+        assert!(schema.contains("  dictionary: pyzy_2\n"));
+        assert!(schema.contains("    - xform/\\bju(?=[1-5]?\\b)/ㄐㄩ/\n"));
+        assert!(schema.contains("    - xform/\\blv(?=[1-5]?\\b)/ㄌㄩ/\n"));
+        assert!(schema.contains("    - xform/\\bzh\\b/ㄓ/\n"));
+        assert!(schema.contains("    - xform/([ㄅ-ㄩ])1/$1/\n"));
+        assert!(schema.contains("    - xform/([ㄅ-ㄩ])5/$1˙/\n"));
+        assert_eq!(schema.matches("xform/").count(), SYLLABLES.len() + 21 + 5);
+
+        let schema = rime_schema("pyzy", "Syllables", false).unwrap();
+        assert!(!schema.contains("preedit_format"));
+        assert!(schema.ends_with("  dictionary: pyzy\n"));
+
+        assert_eq!(rime_schema("Pyzy", "Syllables", false), None);
+        assert_eq!(rime_schema("2pyzy", "Syllables", false), None);
+        assert_eq!(rime_schema("py-zy", "Syllables", false), None);
+        assert_eq!(rime_schema("pyzy", " ", false), None);
+        assert_eq!(rime_schema("pyzy", "a\nb", false), None);
+    }
+
+    #[test]
+    fn rime_dict_test() {
+        let dict = rime_dict("pyzy", NotationKind::Pinyin).unwrap();
+        assert!(dict.contains("\nname: pyzy\n"));
+        assert!(dict.contains("\n...\n\n"));
+        assert!(dict.contains("\nzhāng\tzhang1\n"));
+        assert!(dict.contains("\nlüè\tlve4\n"));
+        assert!(dict.contains("\nyuán\tyuan2\n"));
+        assert_eq!(
+            dict.lines().filter(|l| l.contains('\t')).count(),
+            SYLLABLES.len() * 5
+        );
+
+        let dict = rime_dict("pyzy", NotationKind::Zhuyin).unwrap();
+        assert!(dict.contains("\nㄓㄤ\tzhang1\n"));
+        assert!(dict.contains("\nㄇㄚ˙\tma5\n"));
+
+        assert_eq!(rime_dict("", NotationKind::Pinyin), None);
+    }
+}
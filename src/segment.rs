@@ -0,0 +1,439 @@
+//! Segmenting pinyin typed without separators.
+//!
+//! A [`Lattice`] holds every syllable that can be read at every position of the
+//! input. With abbreviations, initials such as `b`, `zh` or `a` also stand for
+//! every syllable they start, as typed in IMEs and contact search.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::iter;
+
+use crate::{is_nasal, Notation, Pinyin, Syllable, SYLLABLES};
+
+/// The longest syllable, in letters
+const LONGEST_SYLLABLE: usize = 6;
+
+/// A stretch of the input and the syllables it can be read as
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    /// The byte offset the stretch starts at
+    pub start: usize,
+    /// The byte offset the stretch ends at
+    pub end: usize,
+    /// The syllables the stretch can be read as
+    pub syllables: Vec<Syllable>,
+    /// True if the stretch is an abbreviated initial rather than a syllable
+    pub abbreviated: bool,
+}
+
+/// Every syllable that can be read at every position of some pinyin
+///
+/// Syllables may carry tone marks; toneless ones have the 5th tone. Spaces and
+/// apostrophes separate syllables.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let lattice = Lattice::new("xian", false);
+/// let paths: Vec<Vec<String>> = lattice
+///     .paths(10)
+///     .iter()
+///     .map(|path| path.iter().map(|edge| edge.syllables[0].to_string()).collect())
+///     .collect();
+/// assert_eq!(paths, [vec!["xian5"], vec!["xi5", "an5"]]);
+///
+/// assert!(Lattice::new("zhongs", true).is_complete());
+/// assert!(!Lattice::new("zhongs", false).is_complete());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lattice {
+    input: String,
+    /// Sorted by start, then longest first
+    edges: Vec<Edge>,
+}
+
+impl Lattice {
+    /// Build the lattice of some pinyin, reading abbreviated initials if asked
+    pub fn new(s: &str, abbreviations: bool) -> Lattice {
+        let input = s.to_lowercase();
+        let ends: Vec<usize> = input
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([input.len()])
+            .collect();
+
+        let mut edges = Vec::new();
+        for start in 0..ends.len() {
+            for end in (start + 1..ends.len().min(start + LONGEST_SYLLABLE + 1)).rev() {
+                let s = &input[ends[start]..ends[end]];
+                if s.contains(is_separator) {
+                    continue;
+                }
+
+                // A syllabic nasal is only read standing alone, so `xian` isn't `xia n`
+                let alone = input[..ends[start]]
+                    .chars()
+                    .next_back()
+                    .is_none_or(is_separator)
+                    && input[ends[end]..].chars().next().is_none_or(is_separator);
+                if let Some(syllable) = Pinyin
+                    .parse(s)
+                    .filter(|syllable| syllable.is_valid() && (alone || !is_nasal(&syllable.rhyme)))
+                {
+                    edges.push(Edge {
+                        start: ends[start],
+                        end: ends[end],
+                        syllables: vec![syllable],
+                        abbreviated: false,
+                    });
+                }
+                // `zh`, `ch` and `sh` are never read as two initials
+                let digraph = matches!(s, "z" | "c" | "s") && input[ends[end]..].starts_with('h');
+                if let Some(syllables) = abbreviation(s).filter(|_| abbreviations && !digraph) {
+                    edges.push(Edge {
+                        start: ends[start],
+                        end: ends[end],
+                        syllables,
+                        abbreviated: true,
+                    });
+                }
+            }
+        }
+
+        Lattice { input, edges }
+    }
+
+    /// Returns the input, lowercased
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns every edge, by start
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Returns the edges starting at a byte offset
+    pub fn edges_from(&self, start: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.start == start)
+    }
+
+    /// Returns true if the whole input reads as syllables
+    pub fn is_complete(&self) -> bool {
+        !self.input.trim_matches(is_separator).is_empty() && self.rest_costs()[0].is_some()
+    }
+
+    /// Returns the best ways of reading the whole input, at most `limit`
+    ///
+    /// Paths with fewer abbreviations come first, then paths with fewer edges,
+    /// then paths taking longer edges first. Paths are found best first, so
+    /// asking for a few of them is quick however many there are.
+    pub fn paths(&self, limit: usize) -> Vec<Vec<&Edge>> {
+        self.paths_where(limit, |_| true)
+    }
+
+    /// Returns the best ways of reading the whole input, at most `limit`,
+    /// dropping every path that starts with edges `keep` rejects
+    ///
+    /// `keep` is given each partial path as it grows by one edge.
+    pub(crate) fn paths_where<F>(&self, limit: usize, mut keep: F) -> Vec<Vec<&Edge>>
+    where
+        F: FnMut(&[&Edge]) -> bool,
+    {
+        let mut ret = Vec::new();
+        let rest = self.rest_costs();
+        let Some(cost) = rest[0].filter(|_| !self.input.trim_matches(is_separator).is_empty())
+        else {
+            return ret;
+        };
+
+        // Partial paths by the cost of their best completion, then by their
+        // edges, with the cost so far and where they end
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((cost, Vec::new(), (0, 0), self.skip_separators(0))));
+        while let Some(Reverse((_, path, (abbreviations, len), i))) = heap.pop() {
+            if ret.len() == limit {
+                break;
+            }
+            if i == self.input.len() {
+                ret.push(path.iter().map(|&e| &self.edges[e]).collect());
+                continue;
+            }
+
+            for (e, edge) in self.edges.iter().enumerate() {
+                if edge.start != i {
+                    continue;
+                }
+                let end = self.skip_separators(edge.end);
+                let Some((rest_abbreviations, rest_len)) = rest[end] else {
+                    continue;
+                };
+                let mut path = path.clone();
+                path.push(e);
+                let edges: Vec<&Edge> = path.iter().map(|&e| &self.edges[e]).collect();
+                if !keep(&edges) {
+                    continue;
+                }
+
+                let done = (abbreviations + edge.abbreviated as usize, len + 1);
+                let cost = (done.0 + rest_abbreviations, done.1 + rest_len);
+                heap.push(Reverse((cost, path, done, end)));
+            }
+        }
+        ret
+    }
+
+    /// Returns, for every byte offset, the fewest abbreviations and then edges
+    /// that read the input from there, or None if it can't be read
+    fn rest_costs(&self) -> Vec<Option<(usize, usize)>> {
+        let mut ret = vec![None; self.input.len() + 1];
+        ret[self.input.len()] = Some((0, 0));
+        for i in (0..self.input.len()).rev() {
+            if !self.input.is_char_boundary(i) {
+                continue;
+            }
+            if self.input[i..].starts_with(is_separator) {
+                ret[i] = ret[self.next_char(i)];
+                continue;
+            }
+            ret[i] = self
+                .edges_from(i)
+                .filter_map(|edge| {
+                    let (abbreviations, len) = ret[edge.end]?;
+                    Some((abbreviations + edge.abbreviated as usize, len + 1))
+                })
+                .min();
+        }
+        ret
+    }
+
+    fn skip_separators(&self, mut i: usize) -> usize {
+        while self.input[i..].starts_with(is_separator) {
+            i = self.next_char(i);
+        }
+        i
+    }
+
+    fn next_char(&self, i: usize) -> usize {
+        i + self.input[i..].chars().next().map_or(0, char::len_utf8)
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '\'' | '’')
+}
+
+/// Returns every syllable an abbreviated initial stands for
+///
+/// `z`, `c` and `s` also stand for `zh`, `ch` and `sh`, and `a`, `o` and `e`
+/// for the syllables without an initial they start.
+fn abbreviation(s: &str) -> Option<Vec<Syllable>> {
+    let consonants: &[&str] = match s {
+        "b" | "p" | "m" | "f" | "d" | "t" | "n" | "l" | "g" | "k" | "h" | "j" | "q" | "x" | "r"
+        | "zh" | "ch" | "sh" | "y" | "w" => &[s],
+        "z" => &["z", "zh"],
+        "c" => &["c", "ch"],
+        "s" => &["s", "sh"],
+        "a" | "o" | "e" => &[""],
+        _ => return None,
+    };
+
+    Some(
+        consonants
+            .iter()
+            .flat_map(|&consonant| {
+                SYLLABLES
+                    .keys()
+                    .filter_map(|syllable| Syllable::with_tone(syllable, 5))
+                    .filter(move |syllable| {
+                        syllable.consonant == consonant
+                            && (!consonant.is_empty() || syllable.rhyme.starts_with(s))
+                    })
+            })
+            .collect(),
+    )
+}
+
+/// Expand abbreviated initials to every syllable sequence they can stand for
+///
+/// Each initial stands for one syllable; `zh`, `ch` and `sh` are always read
+/// as one initial, while `z`, `c` and `s` also stand for them. `a`, `o` and `e`
+/// stand for the syllables without an initial they start. Syllables are given
+/// as numbered pinyin with the 5th tone. Nothing is given if the input isn't
+/// only initials.
+///
+/// The sequences are made as they're asked for, as there are millions of them
+/// for five initials; take the ones needed.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let expansions: Vec<Vec<String>> = expand_initials("bj").collect();
+/// assert!(expansions.contains(&vec!["bei5".to_owned(), "jing5".to_owned()]));
+///
+/// let expansions: Vec<Vec<String>> = expand_initials("zs").collect();
+/// assert!(expansions.contains(&vec!["zhong5".to_owned(), "shan5".to_owned()]));
+/// assert!(expand_initials("zhsh").count() < expansions.len());
+///
+/// assert_eq!(expand_initials("bei").next(), None);
+/// ```
+pub fn expand_initials<S>(s: S) -> impl Iterator<Item = Vec<String>>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().to_lowercase();
+    let letters: Vec<char> = s.chars().filter(|&c| !is_separator(c)).collect();
+
+    let mut slots = Vec::new();
+    let mut i = 0;
+    while i < letters.len() {
+        let len = match letters[i..] {
+            ['z' | 'c' | 's', 'h', ..] => 2,
+            _ => 1,
+        };
+        let initial: String = letters[i..i + len].iter().collect();
+        match abbreviation(&initial) {
+            Some(syllables) => slots.push(syllables),
+            None => {
+                slots.clear();
+                break;
+            }
+        }
+        i += len;
+    }
+
+    // The syllable picked in each slot, counting up with the last slot fastest
+    let mut picks = match slots.is_empty() || slots.iter().any(Vec::is_empty) {
+        true => None,
+        false => Some(vec![0; slots.len()]),
+    };
+    iter::from_fn(move || {
+        let current = picks.as_mut()?;
+        let ret = current
+            .iter()
+            .zip(&slots)
+            .map(|(&pick, syllables)| syllables[pick].to_string())
+            .collect();
+
+        let mut last = true;
+        for (pick, syllables) in current.iter_mut().zip(&slots).rev() {
+            *pick += 1;
+            if *pick < syllables.len() {
+                last = false;
+                break;
+            }
+            *pick = 0;
+        }
+        if last {
+            picks = None;
+        }
+        Some(ret)
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn paths(s: &str, abbreviations: bool) -> Vec<String> {
+        Lattice::new(s, abbreviations)
+            .paths(100)
+            .iter()
+            .map(|path| {
+                path.iter()
+                    .map(|edge| match edge.abbreviated {
+                        true => format!("{}*", &s.to_lowercase()[edge.start..edge.end]),
+                        false => edge.syllables[0].to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lattice_test() {
+        assert_eq!(paths("xian", false), ["xian5", "xi5 an5"]);
+        assert_eq!(paths("xi'an", false), ["xi5 an5"]);
+        assert_eq!(paths("Xī'ān", false), ["xi1 an1"]);
+        assert_eq!(paths("zhongguo", false), ["zhong5 guo5", "zhong5 gu5 o5"]);
+        assert_eq!(paths("beijing", false), ["bei5 jing5"]);
+        assert_eq!(paths("nǚ ren", false), ["nv3 ren5"]);
+        // Syllabic nasals only stand alone
+        assert_eq!(paths("ńg", false), ["ng2"]);
+        assert_eq!(paths("ng'a", false), ["ng5 a5"]);
+        assert_eq!(paths("tan", false), ["tan5"]);
+        assert!(paths("zhongs", false).is_empty());
+        assert!(paths("", false).is_empty());
+        assert!(paths(" '", false).is_empty());
+
+        assert_eq!(
+            paths("zhongs", true),
+            ["zhong5 s*", "zh* o5 n* g* s*", "zh* o* n* g* s*"]
+        );
+        assert_eq!(paths("bj", true), ["b* j*"]);
+        assert_eq!(paths("zhsh", true), ["zh* sh*"]);
+        assert_eq!(paths("xian", true)[..2], ["xian5", "xi5 an5"]);
+        assert!(paths("xian", true).contains(&"xi5 a* n*".to_owned()));
+        assert!(paths("iu", true).is_empty());
+        assert_eq!(paths("xian", true).len(), 5);
+
+        // Thousands of paths with abbreviations, but only the best are found,
+        // growing a few hundred partial paths
+        let lattice = Lattice::new(&"zhonghuarenmingongheguo".repeat(4), true);
+        let mut partial = 0;
+        let paths = lattice.paths_where(20, |_| {
+            partial += 1;
+            true
+        });
+        assert_eq!(paths.len(), 20);
+        assert_eq!(paths[0].len(), 28);
+        assert!(paths[0].iter().all(|edge| !edge.abbreviated));
+        assert!(partial < 1000, "{}", partial);
+        assert_eq!(Lattice::new("xian", false).paths(1).len(), 1);
+        assert!(Lattice::new("xian", false).paths(0).is_empty());
+
+        let lattice = Lattice::new("zs", true);
+        assert!(lattice.is_complete());
+        assert_eq!(lattice.edges_from(1).count(), 1);
+        let edge = lattice.edges_from(0).next().unwrap();
+        assert!(edge.syllables.contains(&Syllable::new("zhong5").unwrap()));
+        assert!(edge.syllables.contains(&Syllable::new("zi5").unwrap()));
+        assert!(!edge.syllables.contains(&Syllable::new("ci5").unwrap()));
+    }
+
+    #[test]
+    fn expand_initials_test() {
+        let expansions: Vec<_> = expand_initials("bj").collect();
+        assert!(expansions.contains(&vec!["bei5".to_owned(), "jing5".to_owned()]));
+        assert!(expansions.iter().all(|e| e.len() == 2));
+        assert!(expansions.iter().all(|e| e[0].starts_with('b')));
+
+        let expansions: Vec<_> = expand_initials("ZH SH").collect();
+        assert!(expansions.contains(&vec!["zhong5".to_owned(), "shan5".to_owned()]));
+        assert!(!expansions.contains(&vec!["zi5".to_owned(), "shan5".to_owned()]));
+        let expansions: Vec<_> = expand_initials("zs").collect();
+        assert!(expansions.contains(&vec!["zhong5".to_owned(), "shan5".to_owned()]));
+        assert!(expansions.contains(&vec!["zi5".to_owned(), "si5".to_owned()]));
+
+        let expansions: Vec<_> = expand_initials("e").collect();
+        assert_eq!(expansions.len(), 5);
+        assert!(expansions.contains(&vec!["er5".to_owned()]));
+        assert!(expand_initials("wa").all(|e| e[1].starts_with('a')));
+
+        assert_eq!(expand_initials("bei").next(), None);
+        assert_eq!(expand_initials("").next(), None);
+
+        // Millions of sequences, made only as they're taken
+        let total: usize = ["z", "g", "r", "m", "y", "h"]
+            .iter()
+            .map(|initial| abbreviation(initial).unwrap().len())
+            .product();
+        assert!(total > 1_000_000, "{}", total);
+        let expansions: Vec<_> = expand_initials("zgrmyh").take(1000).collect();
+        assert_eq!(expansions.len(), 1000);
+        assert!(expansions.iter().all(|e| e.len() == 6));
+        assert_eq!(expansions[0][..5], expansions[1][..5]);
+        assert_eq!(expand_initials("bj").count(), 224);
+    }
+}
@@ -0,0 +1,600 @@
+//! Shuangpin (double pinyin).
+//!
+//! A Shuangpin scheme types every syllable with two keys: one for the initial
+//! and one for the final, as pinyin spells them (`y` and `w` count as initials,
+//! and `ü` after `j`, `q`, `x` and `y` is typed as `u`). Syllables without an
+//! initial follow a rule of each scheme. Schemes are written as a short mapping
+//! description, so a user-defined scheme is loaded just like the built-in ones.
+use std::collections::HashMap;
+
+use crate::{is_nasal, lookup_syllable, split, Notation, Syllable, SYLLABLES};
+
+static INITIALS: [&str; 23] = [
+    "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "zh", "ch", "sh", "r",
+    "z", "c", "s", "y", "w",
+];
+
+static FINALS: [&str; 35] = [
+    "a", "o", "e", "ai", "ei", "ao", "ou", "an", "en", "ang", "eng", "ong", "er", "i", "ia", "ie",
+    "iao", "iu", "ian", "in", "iang", "ing", "iong", "u", "ua", "uo", "uai", "ui", "uan", "un",
+    "uang", "v", "ue", "ve", "ueng",
+];
+
+static XIAOHE: &str = "
+zh = v
+ch = i
+sh = u
+iu = q
+ei = w
+e = e
+uan = r
+ue ve = t
+un = y
+u = u
+i = i
+o uo = o
+ie = p
+a = a
+iong ong = s
+ai = d
+en = f
+eng ueng = g
+ang = h
+an = j
+ing uai = k
+iang uang = l
+ou = z
+ia ua = x
+ao = c
+ui v = v
+in = b
+iao = n
+ian = m
+zero = spelled
+";
+
+static ZIRANMA: &str = "
+zh = v
+ch = i
+sh = u
+iu = q
+ia ua = w
+e = e
+uan = r
+ue ve = t
+ing uai = y
+u = u
+i = i
+o uo = o
+un = p
+a = a
+iong ong = s
+iang uang = d
+en = f
+eng ueng = g
+ang = h
+an = j
+ao = k
+ai = l
+ei = z
+ie = x
+iao = c
+ui v = v
+ou = b
+in = n
+ian = m
+zero = spelled
+";
+
+static MICROSOFT: &str = "
+zh = v
+ch = i
+sh = u
+iu = q
+ia ua = w
+e = e
+uan er = r
+ue = t
+uai v = y
+u = u
+i = i
+o uo = o
+un = p
+a = a
+iong ong = s
+iang uang = d
+en = f
+eng ueng = g
+ang = h
+an = j
+ao = k
+ai = l
+ing = ;
+ei = z
+ie = x
+iao = c
+ui ve = v
+ou = b
+in = n
+ian = m
+zero = o
+";
+
+static SOGOU: &str = "
+zh = v
+ch = i
+sh = u
+iu = q
+ia ua = w
+e = e
+uan er = r
+ue ve = t
+uai v = y
+u = u
+i = i
+o uo = o
+un = p
+a = a
+iong ong = s
+iang uang = d
+en = f
+eng ueng = g
+ang = h
+an = j
+ao = k
+ai = l
+ing = ;
+ei = z
+ie = x
+iao = c
+ui = v
+ou = b
+in = n
+ian = m
+zero = o
+";
+
+static ZHINENG_ABC: &str = "
+zh = a
+ch = e
+sh = v
+ei = q
+ian = w
+e = e
+iu er = r
+iang uang = t
+ing = y
+u = u
+i = i
+o uo = o
+uan = p
+a = a
+iong ong = s
+ia ua = d
+en = f
+eng ueng = g
+ang = h
+an = j
+ao = k
+ai = l
+iao = z
+ie = x
+in uai = c
+v = v
+ou = b
+un = n
+ue ui ve = m
+zero = o
+";
+
+static JIAJIA: &str = "
+zh = v
+ch = u
+sh = i
+er ing = q
+ei = w
+e = e
+en = r
+eng ueng = t
+iong ong = y
+u = u
+i = i
+o uo = o
+ou = p
+a = a
+ai = s
+ao = d
+an = f
+ang = g
+iang uang = h
+ian = j
+iao = k
+in = l
+un = z
+uai ue ve = x
+uan = c
+ui v = v
+ia ua = b
+iu = n
+ie = m
+zero = first
+";
+
+/// How a scheme types syllables without an initial
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZeroInitial {
+    /// One-letter finals typed twice, two-letter finals as spelled, and longer
+    /// finals as the first letter and the final key (`aa`, `ai`, `ah`)
+    Spelled,
+    /// The first letter of the final, then the final key (`aa`, `as`, `ag`)
+    FirstLetter,
+    /// A fixed key, then the final key (`oa`, `ol`, `oh`)
+    Key(char),
+}
+
+/// A Shuangpin scheme
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let xiaohe = ShuangpinScheme::xiaohe();
+/// assert_eq!(encode_shuangpin("zhuang1", &xiaohe), Some("vl".to_owned()));
+/// assert_eq!(convert("vl", &xiaohe, &Zhuyin), Some("ㄓㄨㄤ˙".to_owned()));
+///
+/// // Too few keys to type every syllable
+/// assert_eq!(ShuangpinScheme::parse("zh = v\nch = i\nsh = u\nzero = o"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShuangpinScheme {
+    initials: HashMap<&'static str, char>,
+    finals: HashMap<&'static str, char>,
+    zero: ZeroInitial,
+    /// Every syllable by its keys
+    table: HashMap<[char; 2], &'static str>,
+}
+
+impl ShuangpinScheme {
+    /// 小鹤双拼
+    pub fn xiaohe() -> ShuangpinScheme {
+        ShuangpinScheme::parse(XIAOHE).unwrap()
+    }
+
+    /// 自然码
+    pub fn ziranma() -> ShuangpinScheme {
+        ShuangpinScheme::parse(ZIRANMA).unwrap()
+    }
+
+    /// 微软双拼
+    pub fn microsoft() -> ShuangpinScheme {
+        ShuangpinScheme::parse(MICROSOFT).unwrap()
+    }
+
+    /// 搜狗双拼
+    pub fn sogou() -> ShuangpinScheme {
+        ShuangpinScheme::parse(SOGOU).unwrap()
+    }
+
+    /// 智能ABC
+    pub fn zhineng_abc() -> ShuangpinScheme {
+        ShuangpinScheme::parse(ZHINENG_ABC).unwrap()
+    }
+
+    /// 拼音加加
+    pub fn jiajia() -> ShuangpinScheme {
+        ShuangpinScheme::parse(JIAJIA).unwrap()
+    }
+
+    /// Load a scheme from a mapping description
+    ///
+    /// Each line gives initials or finals, then `=` and their key, such as
+    /// `iang uang = l`. Initials of one letter are typed with that letter unless
+    /// given. `zero = spelled`, `zero = first` or `zero = <key>` picks the rule
+    /// for syllables without an initial. Blank lines and lines starting with `#`
+    /// are skipped.
+    ///
+    /// Returns None if invalid input, if a syllable can't be typed, or if two
+    /// syllables would be typed the same. The one exception is `lo`, typed as
+    /// `luo` in every scheme.
+    pub fn parse(description: &str) -> Option<ShuangpinScheme> {
+        let mut initials = HashMap::new();
+        let mut finals = HashMap::new();
+        let mut zero = None;
+
+        for line in description.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (names, key) = line.split_once('=')?;
+            let key = key.trim();
+            if names.trim() == "zero" {
+                zero = Some(match key {
+                    "spelled" => ZeroInitial::Spelled,
+                    "first" => ZeroInitial::FirstLetter,
+                    _ => ZeroInitial::Key(single_key(key)?),
+                });
+                continue;
+            }
+
+            let key = single_key(key)?;
+            for name in names.split_whitespace() {
+                if let Some(&initial) = INITIALS.iter().find(|&&i| i == name) {
+                    initials.insert(initial, key);
+                } else {
+                    let &final_ = FINALS.iter().find(|&&f| f == name)?;
+                    finals.insert(final_, key);
+                }
+            }
+        }
+
+        for initial in INITIALS.iter().filter(|i| i.len() == 1) {
+            initials
+                .entry(initial)
+                .or_insert(initial.chars().next().unwrap());
+        }
+
+        let mut ret = ShuangpinScheme {
+            initials,
+            finals,
+            zero: zero?,
+            table: HashMap::new(),
+        };
+        // Syllabic nasals aren't typed in Shuangpin
+        for syllable in SYLLABLES.keys().filter(|syllable| !is_nasal(syllable)) {
+            let keys = ret.keys(syllable)?;
+            match ret.table.insert(keys, syllable) {
+                None => {}
+                // `lo` shares its keys with `luo`, which is read instead
+                Some("luo") if *syllable == "lo" => {
+                    ret.table.insert(keys, "luo");
+                }
+                Some("lo") if *syllable == "luo" => {}
+                Some(_) => return None,
+            }
+        }
+        Some(ret)
+    }
+
+    /// Returns the rule for syllables without an initial
+    pub fn zero_initial(&self) -> ZeroInitial {
+        self.zero
+    }
+
+    /// Returns the keys of a syllable, spelt without a tone as in the syllable table
+    fn keys(&self, syllable: &str) -> Option<[char; 2]> {
+        let numbered = format!("{}5", syllable);
+        let (consonant, rhyme, _) = split(&numbered).filter(|(_, rhyme, _)| !is_nasal(rhyme))?;
+        let rhyme = match consonant {
+            "j" | "q" | "x" | "y" => rhyme.replace('v', "u"),
+            _ => rhyme.to_owned(),
+        };
+        let final_key = || self.finals.get(rhyme.as_str()).copied();
+
+        if !consonant.is_empty() {
+            return Some([*self.initials.get(consonant)?, final_key()?]);
+        }
+
+        let first = rhyme.chars().next()?;
+        Some(match self.zero {
+            // Two-letter finals such as `er` need no key of their own
+            ZeroInitial::Spelled => match rhyme.len() {
+                1 => [first, first],
+                2 => [first, rhyme.chars().nth(1)?],
+                _ => [first, final_key()?],
+            },
+            ZeroInitial::FirstLetter => [first, final_key()?],
+            ZeroInitial::Key(key) => [key, final_key()?],
+        })
+    }
+}
+
+/// Returns the only character of a key name
+fn single_key(key: &str) -> Option<char> {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(key), None) if !key.is_whitespace() => Some(key.to_ascii_lowercase()),
+        _ => None,
+    }
+}
+
+impl Notation for ShuangpinScheme {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        match &decode_shuangpin(s, self)?[..] {
+            [syllable] => Syllable::new(syllable),
+            _ => None,
+        }
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_shuangpin(syllable.to_string(), self)
+    }
+}
+
+/// Encode Shuangpin
+///
+/// Shuangpin has no tones, so the tone is checked and then dropped.
+/// Returns None on a missing tone or invalid input, and for the syllabic nasals,
+/// which Shuangpin doesn't type.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let xiaohe = ShuangpinScheme::xiaohe();
+/// assert_eq!(encode_shuangpin("shuang1", &xiaohe), Some("ul".to_owned()));
+/// assert_eq!(encode_shuangpin("ang2", &xiaohe), Some("ah".to_owned()));
+/// assert_eq!(encode_shuangpin("ang2", &ShuangpinScheme::microsoft()), Some("oh".to_owned()));
+///
+/// assert_eq!(encode_shuangpin("shuang", &xiaohe), None);
+/// ```
+pub fn encode_shuangpin<S>(s: S, scheme: &ShuangpinScheme) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (syllable, _, _, _) = lookup_syllable(s.as_ref())?;
+    Some(scheme.keys(syllable)?.iter().collect())
+}
+
+/// Decode Shuangpin keys to numbered pinyin syllables
+///
+/// Every two keys are one syllable, which always has the 5th tone. Whitespace
+/// is skipped. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     decode_shuangpin("nihk", &ShuangpinScheme::ziranma()),
+///     Some(vec!["ni5".to_owned(), "hao5".to_owned()])
+/// );
+/// ```
+pub fn decode_shuangpin<S>(s: S, scheme: &ShuangpinScheme) -> Option<Vec<String>>
+where
+    S: AsRef<str>,
+{
+    let keys: Vec<char> = s
+        .as_ref()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if keys.is_empty() || !keys.len().is_multiple_of(2) {
+        return None;
+    }
+
+    keys.chunks(2)
+        .map(|pair| {
+            let syllable = scheme.table.get(&[pair[0], pair[1]])?;
+            Some(format!("{}5", syllable))
+        })
+        .collect()
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[allow(dead_code)]
+    fn schemes() -> [ShuangpinScheme; 6] {
+        [
+            ShuangpinScheme::xiaohe(),
+            ShuangpinScheme::ziranma(),
+            ShuangpinScheme::microsoft(),
+            ShuangpinScheme::sogou(),
+            ShuangpinScheme::zhineng_abc(),
+            ShuangpinScheme::jiajia(),
+        ]
+    }
+
+    #[test]
+    fn encode_shuangpin_test() {
+        let [xiaohe, ziranma, microsoft, sogou, abc, jiajia] = schemes();
+
+        assert_eq!(encode_shuangpin("zhong1", &xiaohe), s!("vs"));
+        assert_eq!(encode_shuangpin("guo2", &xiaohe), s!("go"));
+        assert_eq!(encode_shuangpin("ju4", &xiaohe), s!("ju"));
+        assert_eq!(encode_shuangpin("lv4", &xiaohe), s!("lv"));
+        assert_eq!(encode_shuangpin("xue2", &xiaohe), s!("xt"));
+        assert_eq!(encode_shuangpin("yuan2", &xiaohe), s!("yr"));
+        assert_eq!(encode_shuangpin("yi1", &xiaohe), s!("yi"));
+        assert_eq!(encode_shuangpin("wo3", &xiaohe), s!("wo"));
+        assert_eq!(encode_shuangpin("a1", &xiaohe), s!("aa"));
+        assert_eq!(encode_shuangpin("ou1", &xiaohe), s!("ou"));
+        assert_eq!(encode_shuangpin("eng1", &xiaohe), s!("eg"));
+        assert_eq!(encode_shuangpin("er4", &xiaohe), s!("er"));
+        assert_eq!(encode_shuangpin("lo5", &xiaohe), s!("lo"));
+
+        assert_eq!(encode_shuangpin("shuang1", &ziranma), s!("ud"));
+        assert_eq!(encode_shuangpin("ai4", &ziranma), s!("ai"));
+        assert_eq!(encode_shuangpin("ying1", &microsoft), s!("y;"));
+        assert_eq!(encode_shuangpin("lve4", &microsoft), s!("lv"));
+        assert_eq!(encode_shuangpin("lv4", &microsoft), s!("ly"));
+        assert_eq!(encode_shuangpin("er4", &microsoft), s!("or"));
+        assert_eq!(encode_shuangpin("lve4", &sogou), s!("lt"));
+        assert_eq!(encode_shuangpin("ou1", &sogou), s!("ob"));
+        assert_eq!(encode_shuangpin("zhang1", &abc), s!("ah"));
+        assert_eq!(encode_shuangpin("chuang1", &abc), s!("et"));
+        assert_eq!(encode_shuangpin("an1", &abc), s!("oj"));
+        assert_eq!(encode_shuangpin("shi4", &jiajia), s!("ii"));
+        assert_eq!(encode_shuangpin("ai4", &jiajia), s!("as"));
+        assert_eq!(encode_shuangpin("er4", &jiajia), s!("eq"));
+
+        assert_eq!(encode_shuangpin("zhong", &xiaohe), None);
+        assert_eq!(encode_shuangpin("r5", &xiaohe), None);
+        assert_eq!(encode_shuangpin("", &xiaohe), None);
+    }
+
+    #[test]
+    fn decode_shuangpin_test() {
+        let [xiaohe, _, microsoft, _, abc, jiajia] = schemes();
+
+        assert_eq!(
+            decode_shuangpin("vsgo", &xiaohe),
+            Some(vec!["zhong5".to_owned(), "guo5".to_owned()])
+        );
+        assert_eq!(
+            decode_shuangpin("YR", &xiaohe),
+            Some(vec!["yvan5".to_owned()])
+        );
+        assert_eq!(
+            decode_shuangpin("aa ai ah", &xiaohe).unwrap(),
+            ["a5", "ai5", "ang5"]
+        );
+        assert_eq!(
+            decode_shuangpin("oooh", &microsoft).unwrap(),
+            ["o5", "ang5"]
+        );
+        assert_eq!(decode_shuangpin("y;", &microsoft).unwrap(), ["ying5"]);
+        assert_eq!(decode_shuangpin("lo", &microsoft).unwrap(), ["luo5"]);
+        assert_eq!(decode_shuangpin("ah", &abc).unwrap(), ["zhang5"]);
+        assert_eq!(decode_shuangpin("eqag", &jiajia).unwrap(), ["er5", "ang5"]);
+
+        assert_eq!(decode_shuangpin("vsg", &xiaohe), None);
+        assert_eq!(decode_shuangpin("bz", &xiaohe), None);
+        assert_eq!(decode_shuangpin("", &xiaohe), None);
+
+        // Every syllable of every scheme reads back
+        for scheme in schemes() {
+            for syllable in SYLLABLES.keys().filter(|&&s| s != "lo" && !is_nasal(s)) {
+                let keys = encode_shuangpin(format!("{}1", syllable), &scheme).unwrap();
+                assert_eq!(
+                    decode_shuangpin(&keys, &scheme),
+                    Some(vec![format!("{}5", syllable)])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scheme_parse_test() {
+        // Xiaohe with its zero-initial rule swapped for a fixed key
+        let custom = XIAOHE.replace("zero = spelled", "# Zero initial\nzero = O\ner = r");
+        let scheme = ShuangpinScheme::parse(&custom).unwrap();
+        assert_eq!(scheme.zero_initial(), ZeroInitial::Key('o'));
+        assert_eq!(encode_shuangpin("ang1", &scheme), s!("oh"));
+        assert_eq!(encode_shuangpin("zhang1", &scheme), s!("vh"));
+        assert_eq!(crate::convert("vh", &scheme, &crate::Zhuyin), s!("ㄓㄤ˙"));
+
+        // Missing the zero-initial rule
+        assert_eq!(
+            ShuangpinScheme::parse(&XIAOHE.replace("zero = spelled", "")),
+            None
+        );
+        // A syllable can't be typed
+        assert_eq!(ShuangpinScheme::parse(&XIAOHE.replace("ian = m", "")), None);
+        // Two syllables typed the same
+        assert_eq!(
+            ShuangpinScheme::parse(&XIAOHE.replace("ian = m", "ian = n")),
+            None
+        );
+        // Unknown names and keys
+        assert_eq!(ShuangpinScheme::parse(&format!("{}\nuu = a", XIAOHE)), None);
+        assert_eq!(ShuangpinScheme::parse(&format!("{}\nu = aa", XIAOHE)), None);
+        assert_eq!(ShuangpinScheme::parse(&format!("{}\nu a", XIAOHE)), None);
+        assert_eq!(ShuangpinScheme::parse(""), None);
+    }
+}
@@ -0,0 +1,256 @@
+//! Taiwanese Braille (國語點字).
+//!
+//! Each zhuyin symbol has its own cell, with single cells for most medial and
+//! final pairs, and every syllable ends with a tone cell, the first tone
+//! included. `ㄍ`/`ㄐ`, `ㄘ`/`ㄑ` and `ㄙ`/`ㄒ` share a cell and are told apart
+//! by the medial.
+use crate::{decode_zhuyin, split_zhuyin_word, Notation, Syllable, Zhuyin};
+
+/// The blank cell written between words
+const BRAILLE_SPACE: char = '\u{2800}';
+
+static TONE_CELLS: [char; 5] = ['⠄', '⠂', '⠈', '⠐', '⠁'];
+static ZHUYIN_TONES: [&str; 5] = ["", "ˊ", "ˇ", "ˋ", "˙"];
+
+#[rustfmt::skip]
+static INITIAL_CELLS: [(char, char); 21] = [
+    ('ㄅ', '⠕'), ('ㄆ', '⠏'), ('ㄇ', '⠍'), ('ㄈ', '⠟'),
+    ('ㄉ', '⠙'), ('ㄊ', '⠋'), ('ㄋ', '⠝'), ('ㄌ', '⠉'),
+    ('ㄍ', '⠅'), ('ㄎ', '⠇'), ('ㄏ', '⠗'),
+    ('ㄐ', '⠅'), ('ㄑ', '⠚'), ('ㄒ', '⠑'),
+    ('ㄓ', '⠁'), ('ㄔ', '⠃'), ('ㄕ', '⠊'), ('ㄖ', '⠛'),
+    ('ㄗ', '⠓'), ('ㄘ', '⠚'), ('ㄙ', '⠑'),
+];
+
+#[rustfmt::skip]
+static RHYME_CELLS: [(&str, char); 36] = [
+    ("ㄚ", '⠜'), ("ㄛ", '⠣'), ("ㄜ", '⠮'), ("ㄝ", '⠢'),
+    ("ㄞ", '⠺'), ("ㄟ", '⠴'), ("ㄠ", '⠩'), ("ㄡ", '⠷'),
+    ("ㄢ", '⠧'), ("ㄣ", '⠥'), ("ㄤ", '⠭'), ("ㄥ", '⠵'), ("ㄦ", '⠱'),
+    ("ㄧ", '⠡'), ("ㄨ", '⠌'), ("ㄩ", '⠳'),
+
+    ("ㄧㄚ", '⠾'), ("ㄧㄝ", '⠬'), ("ㄧㄠ", '⠪'), ("ㄧㄡ", '⠎'),
+    ("ㄧㄢ", '⠞'), ("ㄧㄣ", '⠹'), ("ㄧㄤ", '⠨'), ("ㄧㄥ", '⠽'),
+    ("ㄨㄚ", '⠔'), ("ㄨㄛ", '⠒'), ("ㄨㄞ", '⠶'), ("ㄨㄟ", '⠫'),
+    ("ㄨㄢ", '⠻'), ("ㄨㄣ", '⠿'), ("ㄨㄤ", '⠸'), ("ㄨㄥ", '⠯'),
+    ("ㄩㄝ", '⠦'), ("ㄩㄢ", '⠘'), ("ㄩㄣ", '⠲'), ("ㄩㄥ", '⠖'),
+];
+
+/// Taiwanese Braille, such as `⠁⠯⠄`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TaiwanBraille;
+
+impl Notation for TaiwanBraille {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Zhuyin.parse(&taiwan_braille_to_zhuyin(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        zhuyin_to_taiwan_braille(Zhuyin.render(syllable)?)
+    }
+}
+
+/// Encode one zhuyin syllable, which must be valid
+fn encode_syllable(zhuyin: &str, ret: &mut String) -> Option<()> {
+    let tone = ZHUYIN_TONES[1..]
+        .iter()
+        .position(|tone| zhuyin.ends_with(tone))
+        .map_or(0, |i| i + 1);
+    let mut rest = &zhuyin[..zhuyin.len() - ZHUYIN_TONES[tone].len()];
+
+    let first = rest.chars().next()?;
+    if let Some(&(_, cell)) = INITIAL_CELLS.iter().find(|(c, _)| *c == first) {
+        ret.push(cell);
+        rest = &rest[first.len_utf8()..];
+    }
+
+    if let Some(&(_, cell)) = RHYME_CELLS.iter().find(|(rhyme, _)| *rhyme == rest) {
+        ret.push(cell);
+    } else {
+        for c in rest.chars() {
+            let &(_, cell) = RHYME_CELLS
+                .iter()
+                .find(|(rhyme, _)| rhyme.starts_with(c) && rhyme.len() == c.len_utf8())?;
+            ret.push(cell);
+        }
+    }
+
+    ret.push(TONE_CELLS[tone]);
+    Some(())
+}
+
+/// Convert zhuyin to Taiwanese Braille
+///
+/// Words are separated by whitespace, and each word is zhuyin syllables written
+/// together. Words are separated by a blank cell in the result.
+/// Returns None if invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_taiwan_braille("ㄇㄚˇ"), Some("⠍⠜⠈".to_owned()));
+/// assert_eq!(
+///     zhuyin_to_taiwan_braille("ㄓㄨㄥㄍㄨㄛˊ"),
+///     Some("⠁⠯⠄⠅⠒⠂".to_owned())
+/// );
+/// ```
+pub fn zhuyin_to_taiwan_braille<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for word in s.as_ref().split_whitespace() {
+        if !ret.is_empty() {
+            ret.push(BRAILLE_SPACE);
+        }
+        for syllable in split_zhuyin_word(word)? {
+            encode_syllable(syllable, &mut ret)?;
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Decode one syllable from the start of `cells`, returning it as zhuyin with the rest of the cells
+fn decode_syllable(cells: &str) -> Option<(String, &str)> {
+    let mut chars = cells.char_indices();
+    let mut initial = None;
+    let mut rhyme = Vec::new();
+
+    let tone = loop {
+        let (i, cell) = chars.next()?;
+        // The first cell is never a tone, so ⠁ there is ㄓ
+        if i == 0 && INITIAL_CELLS.iter().any(|&(_, c)| c == cell) {
+            initial = Some(cell);
+        } else if let Some(tone) = TONE_CELLS.iter().position(|&c| c == cell) {
+            break tone;
+        } else {
+            let &(zhuyin, _) = RHYME_CELLS.iter().find(|&&(_, c)| c == cell)?;
+            rhyme.push(zhuyin);
+        }
+    };
+    let rest = chars.as_str();
+
+    let mut ret = String::with_capacity(12);
+    if let Some(cell) = initial {
+        // ㄐ, ㄑ and ㄒ only come before ㄧ and ㄩ
+        let palatal = rhyme
+            .first()
+            .is_some_and(|rhyme| rhyme.starts_with('ㄧ') || rhyme.starts_with('ㄩ'));
+        let &(c, _) = INITIAL_CELLS
+            .iter()
+            .filter(|&&(_, c)| c == cell)
+            .find(|&&(c, _)| palatal == matches!(c, 'ㄐ' | 'ㄑ' | 'ㄒ'))
+            .or_else(|| INITIAL_CELLS.iter().find(|&&(_, c)| c == cell))?;
+        ret.push(c);
+    }
+    rhyme.iter().for_each(|rhyme| ret.push_str(rhyme));
+    ret.push_str(ZHUYIN_TONES[tone]);
+
+    // Only keep syllables zhuyin itself accepts
+    decode_zhuyin(&ret)?;
+    Some((ret, rest))
+}
+
+/// Convert Taiwanese Braille to zhuyin
+///
+/// Words are separated by a blank cell or whitespace, and the syllables of each
+/// word are written together in the result. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(taiwan_braille_to_zhuyin("⠍⠜⠈"), Some("ㄇㄚˇ".to_owned()));
+/// assert_eq!(
+///     taiwan_braille_to_zhuyin("⠁⠯⠄⠅⠒⠂"),
+///     Some("ㄓㄨㄥㄍㄨㄛˊ".to_owned())
+/// );
+/// ```
+pub fn taiwan_braille_to_zhuyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for word in s
+        .as_ref()
+        .split(|c: char| c == BRAILLE_SPACE || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+    {
+        if !ret.is_empty() {
+            ret.push(' ');
+        }
+
+        let mut rest = word;
+        while !rest.is_empty() {
+            let (syllable, next) = decode_syllable(rest)?;
+            ret.push_str(&syllable);
+            rest = next;
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn zhuyin_to_taiwan_braille_test() {
+        assert_eq!(zhuyin_to_taiwan_braille("ㄅㄚ"), s!("⠕⠜⠄"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄓ"), s!("⠁⠄"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄓ˙"), s!("⠁⠁"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄐㄧㄚ"), s!("⠅⠾⠄"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄍㄨㄚ"), s!("⠅⠔⠄"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄒㄩㄝˊ"), s!("⠑⠦⠂"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄙˋ"), s!("⠑⠐"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄌㄩˋ"), s!("⠉⠳⠐"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄦˊ"), s!("⠱⠂"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄧㄡˇ"), s!("⠎⠈"));
+        assert_eq!(zhuyin_to_taiwan_braille("ㄝ"), s!("⠢⠄"));
+        assert_eq!(
+            zhuyin_to_taiwan_braille("ㄨㄛˇㄇㄣ˙ ㄒㄩㄝˊㄒㄧˊ"),
+            s!("⠒⠈⠍⠥⠁\u{2800}⠑⠦⠂⠑⠡⠂")
+        );
+
+        assert_eq!(zhuyin_to_taiwan_braille("ㄐˇ"), None);
+        assert_eq!(zhuyin_to_taiwan_braille("ma3"), None);
+        assert_eq!(zhuyin_to_taiwan_braille(""), None);
+    }
+
+    #[test]
+    fn taiwan_braille_to_zhuyin_test() {
+        assert_eq!(taiwan_braille_to_zhuyin("⠕⠜⠄"), s!("ㄅㄚ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠁⠄"), s!("ㄓ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠁⠁"), s!("ㄓ˙"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠅⠾⠄"), s!("ㄐㄧㄚ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠅⠔⠄"), s!("ㄍㄨㄚ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠑⠦⠂"), s!("ㄒㄩㄝˊ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠑⠐"), s!("ㄙˋ"));
+        assert_eq!(taiwan_braille_to_zhuyin("⠚⠡⠂"), s!("ㄑㄧˊ"));
+        assert_eq!(
+            taiwan_braille_to_zhuyin("⠒⠈⠍⠥⠁\u{2800}⠑⠦⠂⠑⠡⠂"),
+            s!("ㄨㄛˇㄇㄣ˙ ㄒㄩㄝˊㄒㄧˊ")
+        );
+        assert_eq!(
+            crate::convert("⠁⠯⠄", &TaiwanBraille, &crate::Pinyin),
+            s!("zhōng")
+        );
+
+        assert_eq!(taiwan_braille_to_zhuyin("⠍⠜"), None);
+        assert_eq!(taiwan_braille_to_zhuyin("⠄"), None);
+        assert_eq!(taiwan_braille_to_zhuyin("⠅⠄"), None);
+        assert_eq!(taiwan_braille_to_zhuyin(""), None);
+    }
+}
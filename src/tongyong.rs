@@ -0,0 +1,332 @@
+//! Tongyong pinyin.
+//!
+//! Tongyong pinyin, used in Taiwan from 2002 to 2008 and still in many place
+//! names, spells `zh`, `q`, `x` as `jh`, `c`, `s`, the apical vowel as `ih`
+//! (jhih, sih), `ü` as `yu` (jyu, lyu), and `eng` after `b`, `p`, `m`, `f`
+//! and `w` as `ong`. The 1st tone is left unmarked, the 2nd to 4th take the
+//! marks of pinyin, and the neutral tone a ring above (˚).
+use std::sync::OnceLock;
+
+use crate::{
+    decode_pinyin, decode_zhuyin, encode_pinyin, lookup_syllable, mark_tone, tone_vowel,
+    unmark_tone, Notation, Syllable, SYLLABLES,
+};
+
+/// Tongyong pinyin, such as `jhāng`
+///
+/// Parsing a syllable without a tone mark gives the 1st tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tongyong;
+
+impl Notation for Tongyong {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_tongyong(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_tongyong(syllable.to_string())
+    }
+}
+
+/// The neutral tone mark, a combining ring above
+const RING: char = '\u{30a}';
+
+/// Returns the Tongyong spelling of an initial
+fn initial(initial: &str) -> &'static str {
+    match initial {
+        "zh" => "jh",
+        "q" => "c",
+        "x" => "s",
+        "b" => "b",
+        "p" => "p",
+        "m" => "m",
+        "f" => "f",
+        "d" => "d",
+        "t" => "t",
+        "n" => "n",
+        "l" => "l",
+        "g" => "g",
+        "k" => "k",
+        "h" => "h",
+        "j" => "j",
+        "ch" => "ch",
+        "sh" => "sh",
+        "r" => "r",
+        "z" => "z",
+        "c" => "c",
+        "s" => "s",
+        _ => "",
+    }
+}
+
+/// Returns the Tongyong spelling of a final, which depends on the initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("zh" | "ch" | "sh" | "r" | "z" | "c" | "s", "i") => "ih",
+        ("", "i") => "yi",
+        ("", "io") => "yo",
+        ("", "m") => "m",
+        ("", "n") => "n",
+        ("", "ng") => "ng",
+        ("", "ia") => "ya",
+        ("", "ie") => "ye",
+        ("", "iao") => "yao",
+        ("", "iu") => "you",
+        ("", "ian") => "yan",
+        ("", "in") => "yin",
+        ("", "iang") => "yang",
+        ("", "ing") => "ying",
+        ("", "iong") => "yong",
+        ("", "u") => "wu",
+        ("", "ua") => "wa",
+        ("", "uo") => "wo",
+        ("", "uai") => "wai",
+        ("", "ui") => "wei",
+        ("", "uan") => "wan",
+        ("", "un") => "wun",
+        ("", "uang") => "wang",
+        ("", "ueng") => "wong",
+        ("", "v") => "yu",
+        ("", "ve") => "yue",
+        ("", "van") => "yuan",
+        ("", "vn") => "yun",
+        ("b" | "p" | "m" | "f", "eng") => "ong",
+        (_, "a") => "a",
+        (_, "o") => "o",
+        (_, "e") => "e",
+        (_, "ai") => "ai",
+        (_, "ei") => "ei",
+        (_, "ao") => "ao",
+        (_, "ou") => "ou",
+        (_, "an") => "an",
+        (_, "en") => "en",
+        (_, "ang") => "ang",
+        (_, "eng") => "eng",
+        (_, "ong") => "ong",
+        (_, "er") => "er",
+        (_, "i") => "i",
+        (_, "ia") => "ia",
+        (_, "ie") => "ie",
+        (_, "iao") => "iao",
+        (_, "iu") => "iou",
+        (_, "ian") => "ian",
+        (_, "in") => "in",
+        (_, "iang") => "iang",
+        (_, "ing") => "ing",
+        (_, "iong") => "yong",
+        (_, "u") => "u",
+        (_, "ua") => "ua",
+        (_, "uo") => "uo",
+        (_, "uai") => "uai",
+        (_, "ui") => "uei",
+        (_, "uan") => "uan",
+        (_, "un") => "un",
+        (_, "uang") => "uang",
+        (_, "v") => "yu",
+        (_, "ve") => "yue",
+        (_, "van") => "yuan",
+        (_, "vn") => "yun",
+        _ => unreachable!(),
+    }
+}
+
+/// Encode Tongyong pinyin
+///
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_tongyong("zhong1"), Some("jhong".to_owned()));
+/// assert_eq!(encode_tongyong("xue2"), Some("syué".to_owned()));
+/// assert_eq!(encode_tongyong("shi4"), Some("shìh".to_owned()));
+///
+/// assert_eq!(encode_tongyong("zhong"), None);
+/// ```
+pub fn encode_tongyong<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, tone) = lookup_syllable(s.as_ref())?;
+
+    let spelling = format!("{}{}", initial(consonant), rhyme(consonant, final_));
+    match tone {
+        1 => Some(spelling),
+        5 => {
+            // After the last letter of a syllabic nasal
+            let i = tone_vowel(&spelling).map_or(spelling.len(), |i| i + 1);
+            Some(format!("{}{}{}", &spelling[..i], RING, &spelling[i..]))
+        }
+        _ => Some(mark_tone(&spelling, tone)),
+    }
+}
+
+/// Decode Tongyong pinyin
+///
+/// A syllable without a tone mark has the 1st tone; a macron also gives the
+/// 1st tone. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_tongyong("Jhong"), Some("zhong1".to_owned()));
+/// assert_eq!(decode_tongyong("syué"), Some("xve2".to_owned()));
+/// assert_eq!(decode_tongyong("de\u{30a}"), Some("de5".to_owned()));
+/// ```
+pub fn decode_tongyong<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let s = s.as_ref().to_lowercase();
+    let (s, tone) = match s.contains(RING) {
+        true => (s.replacen(RING, "", 1), 5),
+        false => match unmark_tone(&s)? {
+            (s, 5) => (s, 1),
+            (s, tone) => (s, tone),
+        },
+    };
+    tongyong_table()
+        .iter()
+        .find(|(tongyong, _)| *tongyong == s)
+        .map(|(_, syllable)| format!("{}{}", syllable, tone))
+}
+
+/// Convert pinyin to Tongyong pinyin
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_tongyong("qíng"), Some("cíng".to_owned()));
+/// ```
+pub fn pinyin_to_tongyong<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_tongyong(decode_pinyin(s)?)
+}
+
+/// Convert zhuyin to Tongyong pinyin
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_tongyong("ㄒㄧㄣ"), Some("sin".to_owned()));
+/// ```
+pub fn zhuyin_to_tongyong<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_tongyong(decode_zhuyin(s)?)
+}
+
+/// Convert Tongyong pinyin to pinyin
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(tongyong_to_pinyin("jhong"), Some("zhōng".to_owned()));
+/// ```
+pub fn tongyong_to_pinyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_pinyin(decode_tongyong(s)?)
+}
+
+/// Every syllable in Tongyong pinyin without its tone, paired with its
+/// numbered pinyin spelling
+fn tongyong_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        SYLLABLES
+            .entries()
+            .map(|(syllable, (consonant, final_))| {
+                let mut tongyong = String::from(initial(consonant));
+                tongyong.push_str(rhyme(consonant, final_));
+                (tongyong, *syllable)
+            })
+            .collect()
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_tongyong_test() {
+        assert_eq!(encode_tongyong("zhong1"), s!("jhong"));
+        assert_eq!(encode_tongyong("tai2"), s!("tái"));
+        assert_eq!(encode_tongyong("bei3"), s!("běi"));
+        assert_eq!(encode_tongyong("qi4"), s!("cì"));
+        assert_eq!(encode_tongyong("xin1"), s!("sin"));
+        assert_eq!(encode_tongyong("xiong2"), s!("syóng"));
+        assert_eq!(encode_tongyong("ju3"), s!("jyǔ"));
+        assert_eq!(encode_tongyong("lv4"), s!("lyù"));
+        assert_eq!(encode_tongyong("nve4"), s!("nyuè"));
+        assert_eq!(encode_tongyong("liu2"), s!("lióu"));
+        assert_eq!(encode_tongyong("gui4"), s!("guèi"));
+        assert_eq!(encode_tongyong("feng1"), s!("fong"));
+        assert_eq!(encode_tongyong("meng4"), s!("mòng"));
+        assert_eq!(encode_tongyong("weng1"), s!("wong"));
+        assert_eq!(encode_tongyong("wen2"), s!("wún"));
+        assert_eq!(encode_tongyong("yu2"), s!("yú"));
+        // Apical vowels
+        assert_eq!(encode_tongyong("zhi1"), s!("jhih"));
+        assert_eq!(encode_tongyong("ri4"), s!("rìh"));
+        assert_eq!(encode_tongyong("zi3"), s!("zǐh"));
+        assert_eq!(encode_tongyong("ci2"), s!("cíh"));
+        assert_eq!(encode_tongyong("si1"), s!("sih"));
+        assert_eq!(encode_tongyong("de5"), s!("de\u{30a}"));
+        assert_eq!(encode_tongyong("er4"), s!("èr"));
+
+        assert_eq!(encode_tongyong("bei"), None);
+        assert_eq!(encode_tongyong("r5"), None);
+        assert_eq!(encode_tongyong(""), None);
+    }
+
+    #[test]
+    fn decode_tongyong_test() {
+        assert_eq!(decode_tongyong("Tái"), s!("tai2"));
+        assert_eq!(decode_tongyong("jhong"), s!("zhong1"));
+        assert_eq!(decode_tongyong("jhōng"), s!("zhong1"));
+        assert_eq!(decode_tongyong("cì"), s!("qi4"));
+        assert_eq!(decode_tongyong("cíh"), s!("ci2"));
+        assert_eq!(decode_tongyong("jyǔ"), s!("jv3"));
+        assert_eq!(decode_tongyong("ma\u{30a}"), s!("ma5"));
+
+        assert_eq!(decode_tongyong("jhōngshān"), None);
+        assert_eq!(decode_tongyong("zhong"), None);
+        assert_eq!(decode_tongyong("tàí"), None);
+        assert_eq!(decode_tongyong(""), None);
+
+        // Every spelling belongs to one syllable
+        for (tongyong, syllable) in tongyong_table() {
+            assert_eq!(decode_tongyong(tongyong), Some(format!("{}1", syllable)));
+        }
+        for syllable in SYLLABLES.keys() {
+            for tone in 1..=5 {
+                let syllable = format!("{}{}", syllable, tone);
+                let tongyong = encode_tongyong(&syllable).unwrap();
+                assert_eq!(decode_tongyong(&tongyong), Some(syllable));
+            }
+        }
+    }
+
+    #[test]
+    fn tongyong_conversion_test() {
+        assert_eq!(pinyin_to_tongyong("zhōu"), s!("jhou"));
+        assert_eq!(zhuyin_to_tongyong("ㄒㄩㄥˊ"), s!("syóng"));
+        assert_eq!(tongyong_to_pinyin("Gāosyóng"), None);
+        assert_eq!(tongyong_to_pinyin("syóng"), s!("xióng"));
+
+        assert_eq!(pinyin_to_tongyong("啊"), None);
+        assert_eq!(zhuyin_to_tongyong("ㄩㄝㄝ"), None);
+        assert_eq!(tongyong_to_pinyin("mau"), None);
+    }
+}
@@ -0,0 +1,234 @@
+//! Character readings from the Unihan database.
+//!
+//! `Unihan_Readings.txt` has one line per character and field, separated by
+//! tabs: `U+4E2D`, `kMandarin`, `zhōng`. The pinyin fields are read, each
+//! reading is parsed into a syllable, and everything else is skipped.
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::{Notation, Pinyin, Syllable};
+
+/// A Unihan field with pinyin readings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum UnihanField {
+    /// `kMandarin`, the most common reading
+    Mandarin,
+    /// `kTGHZ2013`, from 通用规范汉字字典
+    Tghz2013,
+    /// `kXHC1983`, from 现代汉语词典
+    Xhc1983,
+    /// `kHanyuPinyin`, from 汉语大字典
+    HanyuPinyin,
+}
+
+impl UnihanField {
+    fn from_name(name: &str) -> Option<UnihanField> {
+        match name {
+            "kMandarin" => Some(UnihanField::Mandarin),
+            "kTGHZ2013" => Some(UnihanField::Tghz2013),
+            "kXHC1983" => Some(UnihanField::Xhc1983),
+            "kHanyuPinyin" => Some(UnihanField::HanyuPinyin),
+            _ => None,
+        }
+    }
+}
+
+/// Readings of characters, loaded from `Unihan_Readings.txt`
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let unihan = Unihan::parse(
+///     "U+884C\tkMandarin\txíng\n\
+///      U+884C\tkXHC1983\t0445.030:háng 1289.010:xíng\n",
+/// );
+/// assert_eq!(unihan.reading('行'), Some("xing2".to_owned()));
+/// assert_eq!(unihan.readings('行'), ["xing2", "hang2"]);
+/// assert_eq!(encode_zhuyin(unihan.reading('行').unwrap()), Some("ㄒㄧㄥˊ".to_owned()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Unihan {
+    readings: HashMap<char, Vec<(UnihanField, Syllable)>>,
+    skipped: usize,
+}
+
+impl Unihan {
+    /// Create an empty set of readings
+    pub fn new() -> Unihan {
+        Unihan::default()
+    }
+
+    /// Load `Unihan_Readings.txt` from a file
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Unihan> {
+        Unihan::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Load `Unihan_Readings.txt` from a reader
+    pub fn read<R: BufRead>(reader: R) -> io::Result<Unihan> {
+        let mut ret = Unihan::new();
+        for line in reader.lines() {
+            ret.add_line(&line?);
+        }
+        Ok(ret)
+    }
+
+    /// Load `Unihan_Readings.txt` from a string
+    pub fn parse(s: &str) -> Unihan {
+        let mut ret = Unihan::new();
+        s.lines().for_each(|line| ret.add_line(line));
+        ret
+    }
+
+    fn add_line(&mut self, line: &str) {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        let mut fields = line.split('\t');
+        let (Some(code), Some(name), Some(value)) = (fields.next(), fields.next(), fields.next())
+        else {
+            self.skipped += 1;
+            return;
+        };
+        let Some(field) = UnihanField::from_name(name) else {
+            return;
+        };
+        let Some(c) = code
+            .strip_prefix("U+")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32)
+        else {
+            self.skipped += 1;
+            return;
+        };
+
+        // kMandarin lists readings; the others list `location:reading,reading`
+        for entry in value.split_whitespace() {
+            let readings = entry.rsplit(':').next().unwrap_or(entry);
+            for reading in readings.split(',') {
+                match Pinyin.parse(reading).filter(Syllable::is_valid) {
+                    Some(syllable) => self.add(c, field, syllable),
+                    None => self.skipped += 1,
+                }
+            }
+        }
+    }
+
+    /// Add a reading of a character
+    pub fn add(&mut self, c: char, field: UnihanField, syllable: Syllable) {
+        let readings = self.readings.entry(c).or_default();
+        if !readings.contains(&(field, syllable.clone())) {
+            readings.push((field, syllable));
+        }
+    }
+
+    /// Returns the number of characters with readings
+    pub fn len(&self) -> usize {
+        self.readings.len()
+    }
+
+    /// Returns true if no character has a reading
+    pub fn is_empty(&self) -> bool {
+        self.readings.is_empty()
+    }
+
+    /// Returns the number of lines and readings that couldn't be parsed
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns every reading of a character as numbered pinyin, most common first
+    ///
+    /// Readings are ordered by field, `kMandarin` first, then in the order of
+    /// the file. Returns an empty list if the character has no reading.
+    pub fn readings(&self, c: char) -> Vec<String> {
+        let mut syllables: Vec<&(UnihanField, Syllable)> =
+            self.readings.get(&c).into_iter().flatten().collect();
+        syllables.sort_by_key(|(field, _)| *field);
+
+        let mut ret = Vec::new();
+        for (_, syllable) in syllables {
+            let syllable = syllable.to_string();
+            if !ret.contains(&syllable) {
+                ret.push(syllable);
+            }
+        }
+        ret
+    }
+
+    /// Returns the readings of a character from one field, as numbered pinyin
+    pub fn field_readings(&self, c: char, field: UnihanField) -> Vec<String> {
+        self.readings
+            .get(&c)
+            .into_iter()
+            .flatten()
+            .filter(|(f, _)| *f == field)
+            .map(|(_, syllable)| syllable.to_string())
+            .collect()
+    }
+
+    /// Returns the most common reading of a character as numbered pinyin
+    ///
+    /// Returns None if the character has no reading.
+    pub fn reading(&self, c: char) -> Option<String> {
+        self.readings(c).into_iter().next()
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    static SAMPLE: &str = "\
+# Unihan_Readings.txt
+#
+U+4E2D\tkDefinition\tcentral; center, middle
+U+4E2D\tkHanyuPinyin\t10021.020:zhōng,zhòng
+U+4E2D\tkMandarin\tzhōng
+U+4E2D\tkTGHZ2013\t482.140:zhōng 485.060:zhòng
+U+4E2D\tkXHC1983\t1551.040:zhōng 1559.050:zhòng
+U+5973\tkMandarin\tnǚ
+U+5973\tkXHC1983\t0841.010:nǚ 1221.050*:rǔ
+U+5417\tkMandarin\tma
+U+20000\tkHanyuPinyin\t10001.010:hē,qiǎn
+U+35DD\tkMandarin\thm̄
+bad line
+U+ZZZZ\tkMandarin\tma
+";
+
+    #[test]
+    fn unihan_test() {
+        let unihan = Unihan::read(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(unihan, Unihan::parse(SAMPLE));
+        assert_eq!(unihan.len(), 4);
+        assert_eq!(unihan.skipped(), 3);
+
+        assert_eq!(unihan.readings('中'), ["zhong1", "zhong4"]);
+        assert_eq!(unihan.reading('中'), Some("zhong1".to_owned()));
+        assert_eq!(
+            unihan.field_readings('中', UnihanField::Mandarin),
+            ["zhong1"]
+        );
+        assert_eq!(
+            unihan.field_readings('中', UnihanField::HanyuPinyin),
+            ["zhong1", "zhong4"]
+        );
+        assert_eq!(unihan.readings('女'), ["nv3", "ru3"]);
+        assert_eq!(unihan.readings('吗'), ["ma5"]);
+        assert_eq!(unihan.readings('𠀀'), ["he1", "qian3"]);
+        assert!(unihan.readings('㗝').is_empty());
+        assert_eq!(unihan.reading('一'), None);
+
+        let mut unihan = Unihan::new();
+        assert!(unihan.is_empty());
+        unihan.add('一', UnihanField::Xhc1983, Syllable::new("yi1").unwrap());
+        unihan.add('一', UnihanField::Xhc1983, Syllable::new("yi1").unwrap());
+        assert_eq!(unihan.readings('一'), ["yi1"]);
+
+        assert!(Unihan::open("/nonexistent/Unihan_Readings.txt").is_err());
+    }
+}
@@ -0,0 +1,345 @@
+//! Word readings from a user dictionary.
+//!
+//! A user dictionary is a text file with one word per line: the word, a tab,
+//! and its reading as numbered pinyin or zhuyin, with or without spaces
+//! between syllables, where the erhua `r5` is a syllable of its own (哪儿
+//! na3r5). Blank lines and lines starting with `#` are ignored. A word on
+//! several lines has several readings, the first one preferred.
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::{decode_zhuyin, split_numbered_word, split_zhuyin_word, Lexicon, Syllable};
+
+/// Why a user dictionary couldn't be loaded
+///
+/// Lines are numbered from 1.
+#[derive(Debug)]
+pub enum UserDictError {
+    /// The file couldn't be read
+    Io(io::Error),
+    /// A line isn't a word and a reading separated by a tab
+    InvalidLine {
+        /// The line number
+        line: usize,
+    },
+    /// A syllable isn't numbered pinyin or zhuyin
+    InvalidSyllable {
+        /// The line number
+        line: usize,
+        /// The syllables written together that couldn't be read
+        syllable: String,
+    },
+    /// A reading doesn't have one syllable per character
+    SyllableCount {
+        /// The line number
+        line: usize,
+        /// The number of characters of the word
+        characters: usize,
+        /// The number of syllables of the reading
+        syllables: usize,
+    },
+}
+
+impl fmt::Display for UserDictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UserDictError::Io(err) => write!(f, "{}", err),
+            UserDictError::InvalidLine { line } => {
+                write!(f, "line {}: expected a word, a tab and a reading", line)
+            }
+            UserDictError::InvalidSyllable { line, syllable } => {
+                write!(f, "line {}: invalid syllable {:?}", line, syllable)
+            }
+            UserDictError::SyllableCount {
+                line,
+                characters,
+                syllables,
+            } => write!(
+                f,
+                "line {}: {} characters but {} syllables",
+                line, characters, syllables
+            ),
+        }
+    }
+}
+
+impl Error for UserDictError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UserDictError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for UserDictError {
+    fn from(err: io::Error) -> UserDictError {
+        UserDictError::Io(err)
+    }
+}
+
+/// Parse a reading, such as `zeng1 guo2fan1` or `ㄗㄥ ㄍㄨㄛˊㄈㄢ`
+///
+/// Returns the syllables written together that couldn't be read on error.
+fn parse_reading(reading: &str) -> Result<Vec<Syllable>, String> {
+    let mut ret = Vec::new();
+    for word in reading.split_whitespace() {
+        let syllables: Option<Vec<Syllable>> = if word.contains(|c| ('ㄅ'..='ㄩ').contains(&c)) {
+            split_zhuyin_word(word).and_then(|syllables| {
+                syllables
+                    .iter()
+                    .map(|syllable| Syllable::new(&decode_zhuyin(syllable)?))
+                    .collect()
+            })
+        } else {
+            let word = word.to_lowercase();
+            split_numbered_word(&word).and_then(|syllables| {
+                syllables
+                    .iter()
+                    .map(|syllable| Syllable::new(syllable).filter(Syllable::is_word_syllable))
+                    .collect()
+            })
+        };
+        ret.extend(syllables.ok_or_else(|| word.to_owned())?);
+    }
+    Ok(ret)
+}
+
+/// Word readings chosen by the user, loaded from a tab-separated file
+///
+/// Layer it over a general dictionary with [`Layered`] so its readings come
+/// first.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let user = UserDict::parse("单\tshan4\n曾\tㄗㄥ\n").unwrap();
+/// assert_eq!(user.word_readings("单"), ["shan4"]);
+/// assert_eq!(user.word_readings("曾"), ["zeng1"]);
+///
+/// let err = UserDict::parse("单\tshan4\n曾\tzeng9\n").unwrap_err();
+/// assert_eq!(err.to_string(), "line 2: invalid syllable \"zeng9\"");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UserDict {
+    words: HashMap<String, Vec<Vec<Syllable>>>,
+    longest_word: usize,
+}
+
+impl UserDict {
+    /// Create an empty dictionary
+    pub fn new() -> UserDict {
+        UserDict::default()
+    }
+
+    /// Load a user dictionary file
+    ///
+    /// Fails on the first line that isn't valid.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<UserDict, UserDictError> {
+        UserDict::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Load a user dictionary from a reader
+    ///
+    /// Fails on the first line that isn't valid.
+    pub fn read<R: BufRead>(reader: R) -> Result<UserDict, UserDictError> {
+        let mut ret = UserDict::new();
+        for (i, line) in reader.lines().enumerate() {
+            ret.add_line(i + 1, &line?)?;
+        }
+        Ok(ret)
+    }
+
+    /// Load a user dictionary from a string
+    ///
+    /// Fails on the first line that isn't valid.
+    pub fn parse(s: &str) -> Result<UserDict, UserDictError> {
+        let mut ret = UserDict::new();
+        for (i, line) in s.lines().enumerate() {
+            ret.add_line(i + 1, line)?;
+        }
+        Ok(ret)
+    }
+
+    fn add_line(&mut self, line_number: usize, line: &str) -> Result<(), UserDictError> {
+        let line = line.trim_end();
+        if line.trim_start().is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        let (word, reading) = line
+            .split_once('\t')
+            .filter(|(word, reading)| !word.trim().is_empty() && !reading.trim().is_empty())
+            .ok_or(UserDictError::InvalidLine { line: line_number })?;
+        let word = word.trim();
+
+        let syllables =
+            parse_reading(reading).map_err(|syllable| UserDictError::InvalidSyllable {
+                line: line_number,
+                syllable,
+            })?;
+        let characters = word.chars().count();
+        if syllables.len() != characters {
+            return Err(UserDictError::SyllableCount {
+                line: line_number,
+                characters,
+                syllables: syllables.len(),
+            });
+        }
+
+        self.add(word, syllables);
+        Ok(())
+    }
+
+    /// Add a reading of a word, one syllable per character
+    pub fn add(&mut self, word: &str, syllables: Vec<Syllable>) {
+        let readings = self.words.entry(word.to_owned()).or_default();
+        if !readings.contains(&syllables) {
+            readings.push(syllables);
+        }
+        self.longest_word = self.longest_word.max(word.chars().count());
+    }
+
+    /// Returns the number of words with readings
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns true if no word has a reading
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Returns the readings of a word as numbered pinyin, syllables separated by
+    /// spaces
+    ///
+    /// Returns an empty list if the word is unknown.
+    pub fn word_readings(&self, word: &str) -> Vec<String> {
+        self.readings(word)
+            .iter()
+            .map(|reading| {
+                reading
+                    .iter()
+                    .map(Syllable::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+}
+
+impl Lexicon for UserDict {
+    fn readings(&self, word: &str) -> Vec<Vec<Syllable>> {
+        self.words.get(word).cloned().unwrap_or_default()
+    }
+
+    fn longest_word(&self) -> usize {
+        self.longest_word
+    }
+
+    fn words(&self) -> Vec<&str> {
+        self.words.keys().map(String::as_str).collect()
+    }
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    static SAMPLE: &str = "\
+# Surnames
+单\tshan4
+单\tShan4
+
+曾\tㄗㄥ
+曾国藩\tzeng1guo2 fan1
+曾国藩\tㄗㄥ ㄍㄨㄛˊㄈㄢˊ\r
+女\tnv3
+哪儿\tna3r5
+";
+
+    #[test]
+    fn user_dict_test() {
+        let user = UserDict::read(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(user, UserDict::parse(SAMPLE).unwrap());
+        assert_eq!(user.len(), 5);
+        assert_eq!(user.longest_word(), 3);
+        assert_eq!(user.word_readings("单"), ["shan4"]);
+        assert_eq!(user.word_readings("曾"), ["zeng1"]);
+        assert_eq!(
+            user.word_readings("曾国藩"),
+            ["zeng1 guo2 fan1", "zeng1 guo2 fan2"]
+        );
+        assert_eq!(user.word_readings("女"), ["nv3"]);
+        assert_eq!(user.word_readings("哪儿"), ["na3 r5"]);
+        assert!(UserDict::new().is_empty());
+
+        let error = |s| UserDict::parse(s).unwrap_err().to_string();
+        assert_eq!(
+            error("单\tshan4\n单 shan4\n"),
+            "line 2: expected a word, a tab and a reading"
+        );
+        assert_eq!(
+            error("单\t\n"),
+            "line 1: expected a word, a tab and a reading"
+        );
+        assert_eq!(
+            error("\tshan4\n"),
+            "line 1: expected a word, a tab and a reading"
+        );
+        assert_eq!(error("单\tshan\n"), "line 1: invalid syllable \"shan\"");
+        assert_eq!(error("单\tbiang2\n"), "line 1: invalid syllable \"biang2\"");
+        assert_eq!(error("曾\tㄗㄥx\n"), "line 1: invalid syllable \"ㄗㄥx\"");
+        assert_eq!(
+            error("# 曾国藩\n\n曾国藩\tzeng1 guo2\n"),
+            "line 3: 3 characters but 2 syllables"
+        );
+        assert!(matches!(
+            UserDict::parse("单\tdan1\n单\tshan9\n"),
+            Err(UserDictError::InvalidSyllable { line: 2, .. })
+        ));
+
+        let err = UserDict::open("/nonexistent/user.tsv").unwrap_err();
+        assert!(matches!(err, UserDictError::Io(_)));
+        assert!(err.source().is_some());
+    }
+
+    #[test]
+    fn layered_test() {
+        let cedict = crate::Cedict::parse(
+            "單 单 [dan1] /bill/\n\
+             單 单 [Shan4] /surname Shan/\n\
+             曾 曾 [ceng2] /once/\n\
+             曾經 曾经 [ceng2 jing1] /once/\n\
+             先生 先生 [xian1 sheng5] /Mister/\n",
+        );
+        let user = UserDict::parse(SAMPLE).unwrap();
+        let layered = crate::Layered::new(&user, &cedict);
+        let pinyin = |s| crate::annotate_text(s, &layered, &crate::Pinyin);
+
+        assert_eq!(pinyin("单先生"), "shàn xiānsheng");
+        assert_eq!(pinyin("曾先生"), "zēng xiānsheng");
+        assert_eq!(pinyin("曾国藩"), "zēngguófān");
+        assert_eq!(pinyin("哪儿"), "nǎr");
+        // A longer word of the general dictionary is still read whole
+        assert_eq!(pinyin("曾经"), "céngjīng");
+        assert_eq!(
+            crate::annotate_text("单先生", &cedict, &crate::Pinyin),
+            "dān xiānsheng"
+        );
+
+        assert_eq!(layered.longest_word(), 3);
+        assert_eq!(layered.readings("单").len(), 2);
+        assert_eq!(layered.readings("先生").len(), 1);
+        assert_eq!(layered.words().len(), 9);
+
+        let index = crate::ReverseIndex::from_lexicon(&layered);
+        let candidates = index.lookup("shan4", crate::LookupOptions::default());
+        assert_eq!(candidates[0].word, "单");
+    }
+}
@@ -0,0 +1,454 @@
+//! Wade-Giles romanization.
+//!
+//! Wade-Giles marks aspiration with an apostrophe (ch'i, t'ai), writes `j`,
+//! `q`, `x` as `ch`, `ch'`, `hs`, and keeps `ü` and `ê`. Tones are superscript
+//! numbers after the syllable (ma³), left out for the neutral tone and often
+//! left out altogether, and the syllables of a name are joined by hyphens.
+//!
+//! Decoding also reads `e` for `ê`, `u` for `ü` in `hsü`, and ASCII tone
+//! numbers.
+use std::sync::OnceLock;
+
+use crate::{
+    decode_pinyin, decode_zhuyin, encode_pinyin, lookup_syllable, Notation, Syllable, SYLLABLES,
+};
+
+/// Wade-Giles, such as `chang¹`
+///
+/// Parsing a syllable without a tone number gives the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WadeGiles;
+
+impl Notation for WadeGiles {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_wade_giles(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_wade_giles(syllable.to_string())
+    }
+}
+
+static SUPERSCRIPTS: [char; 5] = ['¹', '²', '³', '⁴', '⁵'];
+
+/// Returns the Wade-Giles spelling of an initial
+fn initial(initial: &str) -> &'static str {
+    match initial {
+        "b" => "p",
+        "p" => "p'",
+        "m" => "m",
+        "f" => "f",
+        "d" => "t",
+        "t" => "t'",
+        "n" => "n",
+        "l" => "l",
+        "g" => "k",
+        "k" => "k'",
+        "h" => "h",
+        "j" | "zh" => "ch",
+        "q" | "ch" => "ch'",
+        "x" => "hs",
+        "sh" => "sh",
+        "r" => "j",
+        "z" => "ts",
+        "c" => "ts'",
+        "s" => "s",
+        _ => "",
+    }
+}
+
+/// Returns the Wade-Giles spelling of a final, which depends on the initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("zh" | "ch" | "sh" | "r", "i") => "ih",
+        ("", "i") => "i",
+        ("", "io") => "yo",
+        ("", "m") => "m",
+        ("", "n") => "n",
+        ("", "ng") => "ng",
+        ("", "ia") => "ya",
+        ("", "ie") => "yeh",
+        ("", "iao") => "yao",
+        ("", "iu") => "yu",
+        ("", "ian") => "yen",
+        ("", "in") => "yin",
+        ("", "iang") => "yang",
+        ("", "ing") => "ying",
+        ("", "iong") => "yung",
+        ("", "u") => "wu",
+        ("", "ua") => "wa",
+        ("", "uo") => "wo",
+        ("", "uai") => "wai",
+        ("", "ui") => "wei",
+        ("", "uan") => "wan",
+        ("", "un") => "wên",
+        ("", "uang") => "wang",
+        ("", "ueng") => "wêng",
+        ("", "v") => "yü",
+        ("", "ve") => "yüeh",
+        ("", "van") => "yüan",
+        ("", "vn") => "yün",
+        ("g" | "k" | "h", "e") => "o",
+        ("g" | "k", "ui") => "uei",
+        ("d" | "t" | "n" | "l" | "zh" | "ch" | "r" | "z" | "c" | "s", "uo") => "o",
+        (_, "a") => "a",
+        (_, "o") => "o",
+        (_, "e") => "ê",
+        (_, "ai") => "ai",
+        (_, "ei") => "ei",
+        (_, "ao") => "ao",
+        (_, "ou") => "ou",
+        (_, "an") => "an",
+        (_, "en") => "ên",
+        (_, "ang") => "ang",
+        (_, "eng") => "êng",
+        (_, "ong") => "ung",
+        (_, "er") => "êrh",
+        (_, "i") => "i",
+        (_, "ia") => "ia",
+        (_, "ie") => "ieh",
+        (_, "iao") => "iao",
+        (_, "iu") => "iu",
+        (_, "ian") => "ien",
+        (_, "in") => "in",
+        (_, "iang") => "iang",
+        (_, "ing") => "ing",
+        (_, "iong") => "iung",
+        (_, "u") => "u",
+        (_, "ua") => "ua",
+        (_, "uo") => "uo",
+        (_, "uai") => "uai",
+        (_, "ui") => "ui",
+        (_, "uan") => "uan",
+        (_, "un") => "un",
+        (_, "uang") => "uang",
+        (_, "v") => "ü",
+        (_, "ve") => "üeh",
+        (_, "van") => "üan",
+        (_, "vn") => "ün",
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the Wade-Giles spelling of a syllable without its tone
+fn spell(consonant: &str, final_: &str) -> String {
+    match (consonant, final_) {
+        ("z", "i") => "tzu".to_owned(),
+        ("c", "i") => "tz'u".to_owned(),
+        ("s", "i") => "ssu".to_owned(),
+        _ => format!("{}{}", initial(consonant), rhyme(consonant, final_)),
+    }
+}
+
+/// Encode Wade-Giles
+///
+/// The tone is a superscript number, left out for the 5th tone.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_wade_giles("chong2"), Some("ch'ung²".to_owned()));
+/// assert_eq!(encode_wade_giles("xue2"), Some("hsüeh²".to_owned()));
+/// assert_eq!(encode_wade_giles("ma5"), Some("ma".to_owned()));
+///
+/// assert_eq!(encode_wade_giles("ma"), None);
+/// ```
+pub fn encode_wade_giles<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, tone) = lookup_syllable(s.as_ref())?;
+
+    let mut ret = spell(consonant, final_);
+    if tone != 5 {
+        ret.push(SUPERSCRIPTS[tone as usize - 1]);
+    }
+    Some(ret)
+}
+
+/// Decode Wade-Giles
+///
+/// The tone may be a superscript or ASCII number; without one the result has
+/// the 5th tone. Typographic apostrophes are read as `'`. Returns None if
+/// invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_wade_giles("Ch'ing¹"), Some("qing1".to_owned()));
+/// assert_eq!(decode_wade_giles("tsê2"), Some("ze2".to_owned()));
+/// assert_eq!(decode_wade_giles("hsu"), Some("xv5".to_owned()));
+/// ```
+pub fn decode_wade_giles<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let s = normalize(s.as_ref());
+    let (s, tone) = match s.chars().next_back()? {
+        c @ '1'..='5' => (&s[..s.len() - 1], c.to_digit(10)? as u8),
+        c => match SUPERSCRIPTS
+            .iter()
+            .position(|&superscript| superscript == c)
+        {
+            Some(i) => (&s[..s.len() - c.len_utf8()], i as u8 + 1),
+            None => (&s[..], 5),
+        },
+    };
+    wade_giles_table()
+        .iter()
+        .find(|(wade_giles, _)| *wade_giles == s)
+        .map(|(_, syllable)| format!("{}{}", syllable, tone))
+}
+
+/// Encode a name as Wade-Giles
+///
+/// The syllables are given as numbered pinyin, joined by hyphens and
+/// capitalized, without tones, as Wade-Giles writes names.
+/// Returns None on a missing tone or invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_wade_giles_word(["ze2", "dong1"]), Some("Tsê-tung".to_owned()));
+/// assert_eq!(encode_wade_giles_word(["bei3", "jing1"]), Some("Pei-ching".to_owned()));
+/// ```
+pub fn encode_wade_giles_word<I, S>(syllables: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut ret = String::new();
+    for syllable in syllables {
+        let syllable = encode_wade_giles(syllable)?;
+        let syllable = syllable.trim_end_matches(SUPERSCRIPTS);
+        if ret.is_empty() {
+            let mut chars = syllable.chars();
+            ret.extend(chars.next()?.to_uppercase());
+            ret.push_str(chars.as_str());
+        } else {
+            ret.push('-');
+            ret.push_str(syllable);
+        }
+    }
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Decode a Wade-Giles name to numbered pinyin syllables
+///
+/// Syllables may be separated by hyphens or whitespace, so a full name such as
+/// `Mao Tsê-tung` can be decoded at once. Returns None if invalid input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(
+///     decode_wade_giles_word("Mao Tsê-tung"),
+///     Some(vec!["mao5".to_owned(), "ze5".to_owned(), "dong5".to_owned()])
+/// );
+/// ```
+pub fn decode_wade_giles_word<S>(s: S) -> Option<Vec<String>>
+where
+    S: AsRef<str>,
+{
+    let ret: Vec<String> = s
+        .as_ref()
+        .split(|c: char| c == '-' || c.is_whitespace())
+        .filter(|syllable| !syllable.is_empty())
+        .map(decode_wade_giles)
+        .collect::<Option<_>>()?;
+
+    if ret.is_empty() {
+        return None;
+    }
+    Some(ret)
+}
+
+/// Convert pinyin to Wade-Giles
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_wade_giles("běi"), Some("pei³".to_owned()));
+/// ```
+pub fn pinyin_to_wade_giles<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_wade_giles(decode_pinyin(s)?)
+}
+
+/// Convert zhuyin to Wade-Giles
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_wade_giles("ㄐㄧㄥ"), Some("ching¹".to_owned()));
+/// ```
+pub fn zhuyin_to_wade_giles<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_wade_giles(decode_zhuyin(s)?)
+}
+
+/// Convert Wade-Giles to pinyin
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(wade_giles_to_pinyin("chung¹"), Some("zhōng".to_owned()));
+/// assert_eq!(wade_giles_to_pinyin("chung"), Some("zhong".to_owned()));
+/// ```
+pub fn wade_giles_to_pinyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_pinyin(decode_wade_giles(s)?)
+}
+
+/// Lowercase, replace typographic apostrophes with `'`, `ê` with `e`, and
+/// `hsü` with `hsu`
+fn normalize(s: &str) -> String {
+    let ret: String = s
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            '’' | '‘' | 'ʼ' | 'ʻ' => '\'',
+            'ê' => 'e',
+            c => c,
+        })
+        .collect();
+    ret.replacen("hsü", "hsu", 1)
+}
+
+/// Every syllable in Wade-Giles without its tone, normalized, paired with its
+/// numbered pinyin spelling
+///
+/// `lo` is left out, since it's spelled like `luo` and `lo` is read as `luo`.
+fn wade_giles_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        SYLLABLES
+            .entries()
+            .filter(|(syllable, _)| **syllable != "lo")
+            .map(|(syllable, (consonant, final_))| {
+                (normalize(&spell(consonant, final_)), *syllable)
+            })
+            .collect()
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_wade_giles_test() {
+        assert_eq!(encode_wade_giles("bei3"), s!("pei³"));
+        assert_eq!(encode_wade_giles("pei2"), s!("p'ei²"));
+        assert_eq!(encode_wade_giles("jing1"), s!("ching¹"));
+        assert_eq!(encode_wade_giles("qing4"), s!("ch'ing⁴"));
+        assert_eq!(encode_wade_giles("xia4"), s!("hsia⁴"));
+        assert_eq!(encode_wade_giles("zhong1"), s!("chung¹"));
+        assert_eq!(encode_wade_giles("chang2"), s!("ch'ang²"));
+        assert_eq!(encode_wade_giles("shan1"), s!("shan¹"));
+        assert_eq!(encode_wade_giles("ren2"), s!("jên²"));
+        assert_eq!(encode_wade_giles("ge1"), s!("ko¹"));
+        assert_eq!(encode_wade_giles("de2"), s!("tê²"));
+        assert_eq!(encode_wade_giles("e4"), s!("ê⁴"));
+        assert_eq!(encode_wade_giles("er4"), s!("êrh⁴"));
+        assert_eq!(encode_wade_giles("feng1"), s!("fêng¹"));
+        assert_eq!(encode_wade_giles("xiong2"), s!("hsiung²"));
+        assert_eq!(encode_wade_giles("yong3"), s!("yung³"));
+        assert_eq!(encode_wade_giles("you3"), s!("yu³"));
+        assert_eq!(encode_wade_giles("yu3"), s!("yü³"));
+        assert_eq!(encode_wade_giles("lve4"), s!("lüeh⁴"));
+        assert_eq!(encode_wade_giles("jie2"), s!("chieh²"));
+        assert_eq!(encode_wade_giles("tian1"), s!("t'ien¹"));
+        assert_eq!(encode_wade_giles("gui4"), s!("kuei⁴"));
+        assert_eq!(encode_wade_giles("hui4"), s!("hui⁴"));
+        assert_eq!(encode_wade_giles("guo2"), s!("kuo²"));
+        assert_eq!(encode_wade_giles("duo1"), s!("to¹"));
+        assert_eq!(encode_wade_giles("zhuo1"), s!("cho¹"));
+        assert_eq!(encode_wade_giles("shuo1"), s!("shuo¹"));
+        assert_eq!(encode_wade_giles("wen4"), s!("wên⁴"));
+        // Apical vowels
+        assert_eq!(encode_wade_giles("zhi1"), s!("chih¹"));
+        assert_eq!(encode_wade_giles("ri4"), s!("jih⁴"));
+        assert_eq!(encode_wade_giles("zi3"), s!("tzu³"));
+        assert_eq!(encode_wade_giles("ci2"), s!("tz'u²"));
+        assert_eq!(encode_wade_giles("si1"), s!("ssu¹"));
+        assert_eq!(encode_wade_giles("de5"), s!("tê"));
+
+        assert_eq!(encode_wade_giles("bei"), None);
+        assert_eq!(encode_wade_giles("r5"), None);
+        assert_eq!(encode_wade_giles(""), None);
+    }
+
+    #[test]
+    fn decode_wade_giles_test() {
+        assert_eq!(decode_wade_giles("pei³"), s!("bei3"));
+        assert_eq!(decode_wade_giles("Ching"), s!("jing5"));
+        assert_eq!(decode_wade_giles("ch’ung2"), s!("chong2"));
+        assert_eq!(decode_wade_giles("jen2"), s!("ren2"));
+        assert_eq!(decode_wade_giles("Hsü¹"), s!("xv1"));
+        assert_eq!(decode_wade_giles("yü"), s!("yv5"));
+        assert_eq!(decode_wade_giles("yu"), s!("you5"));
+        assert_eq!(decode_wade_giles("ssu"), s!("si5"));
+        assert_eq!(decode_wade_giles("erh4"), s!("er4"));
+        assert_eq!(decode_wade_giles("lo"), s!("luo5"));
+
+        assert_eq!(decode_wade_giles("pei-ching"), None);
+        assert_eq!(decode_wade_giles("pei⁶"), None);
+        assert_eq!(decode_wade_giles("zhong"), None);
+        assert_eq!(decode_wade_giles(""), None);
+
+        // Every spelling belongs to one syllable
+        for (wade_giles, syllable) in wade_giles_table() {
+            assert_eq!(
+                decode_wade_giles(wade_giles),
+                Some(format!("{}5", syllable))
+            );
+        }
+    }
+
+    #[test]
+    fn wade_giles_word_test() {
+        assert_eq!(encode_wade_giles_word(["mao2"]), s!("Mao"));
+        assert_eq!(encode_wade_giles_word(["tai2", "bei3"]), s!("T'ai-pei"));
+        assert_eq!(encode_wade_giles_word(["bei3", "jing"]), None);
+        assert_eq!(encode_wade_giles_word(Vec::<&str>::new()), None);
+
+        assert_eq!(
+            decode_wade_giles_word("Kao-hsiung"),
+            Some(vec!["gao5".to_owned(), "xiong5".to_owned()])
+        );
+        assert_eq!(
+            decode_wade_giles_word(" Ssu-ma  Ch'ien "),
+            Some(vec!["si5".to_owned(), "ma5".to_owned(), "qian5".to_owned()])
+        );
+        assert_eq!(decode_wade_giles_word("Peking"), None);
+        assert_eq!(decode_wade_giles_word("-"), None);
+    }
+
+    #[test]
+    fn wade_giles_conversion_test() {
+        assert_eq!(pinyin_to_wade_giles("zhōu"), s!("chou¹"));
+        assert_eq!(zhuyin_to_wade_giles("ㄒㄩㄥˊ"), s!("hsiung²"));
+        assert_eq!(wade_giles_to_pinyin("Mao²"), s!("máo"));
+
+        assert_eq!(pinyin_to_wade_giles("啊"), None);
+        assert_eq!(zhuyin_to_wade_giles("ㄩㄝㄝ"), None);
+        assert_eq!(wade_giles_to_pinyin("mau"), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! XCompose definitions for typing pinyin with the Compose key.
+//!
+//! A vowel followed by a tone number types the vowel with its tone mark:
+//! `<Multi_key> a 3` types `ǎ`. `ü` is typed `v` and `ê` is typed `e ^`, as
+//! neither is on most keyboards, and the syllabic nasals `m`, `n` and `ng`
+//! take tones the same way. Capital letters give capital forms.
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::{encode_pinyin, typed_syllable, PINYIN_TONES, SYLLABLES};
+
+/// Combining macron, acute, caron and grave accents, by tone number
+static COMBINING_TONES: [char; 4] = ['\u{304}', '\u{301}', '\u{30C}', '\u{300}'];
+
+/// Returns the keysym name of a key
+fn keysym(key: char) -> String {
+    match key {
+        '^' => "asciicircum".to_owned(),
+        _ => key.to_string(),
+    }
+}
+
+/// Returns some text with a tone mark on its first letter, composed where
+/// Unicode can
+fn mark(base: &str, tone: usize) -> String {
+    let mut chars = base.chars();
+    let first = chars.next().unwrap_or_default();
+    let marked = match (first, tone) {
+        ('ê', 2) => "ế".to_owned(),
+        ('ê', 4) => "ề".to_owned(),
+        ('m', 2) => "ḿ".to_owned(),
+        ('n', 2) => "ń".to_owned(),
+        ('n', 3) => "ň".to_owned(),
+        ('n', 4) => "ǹ".to_owned(),
+        _ => format!("{}{}", first, COMBINING_TONES[tone - 1]),
+    };
+    marked + chars.as_str()
+}
+
+/// Capitalize the first letter
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Generate XCompose definitions for tone-marked pinyin
+///
+/// The definitions include the locale's own Compose file first, so they add to
+/// it. If `syllables` is true, every syllable can also be typed whole with a
+/// tone from 1 to 4, such as `<Multi_key> <m> <a> <3>` for `mǎ`. Some of these
+/// start with a sequence of the locale's file, such as `<m> <u>` for `µ`, and
+/// replace it.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// let compose = xcompose(true);
+/// assert!(compose.contains("\n<Multi_key> <a> <3> : \"ǎ\"\n"));
+/// assert!(compose.contains("\n<Multi_key> <V> <4> : \"Ǜ\"\n"));
+/// assert!(compose.contains("\n<Multi_key> <m> <a> <3> : \"mǎ\"\n"));
+/// assert!(compose.contains("\n<Multi_key> <Z> <h> <a> <n> <g> <1> : \"Zhāng\"\n"));
+/// ```
+pub fn xcompose(syllables: bool) -> String {
+    // Keys typed and the text they type
+    let mut sequences: Vec<(String, String)> = Vec::new();
+
+    for vowel_tones in PINYIN_TONES.iter() {
+        let key = match vowel_tones[4] {
+            'ü' => 'v',
+            c => c,
+        };
+        for (tone, &toned) in vowel_tones[..4].iter().enumerate() {
+            sequences.push((format!("{}{}", key, tone + 1), toned.to_string()));
+        }
+        if key == 'v' {
+            sequences.push(("v5".to_owned(), "ü".to_owned()));
+        }
+    }
+    for (keys, base) in [("e^", "ê"), ("m", "m"), ("n", "n"), ("ng", "ng")] {
+        for tone in 1..=4 {
+            sequences.push((format!("{}{}", keys, tone), mark(base, tone)));
+        }
+    }
+    sequences.push(("e^5".to_owned(), "ê".to_owned()));
+
+    if syllables {
+        for syllable in SYLLABLES.keys() {
+            for tone in 1..=4 {
+                if let Some(pinyin) = encode_pinyin(format!("{}{}", syllable, tone)) {
+                    sequences.push((format!("{}{}", typed_syllable(syllable), tone), pinyin));
+                }
+            }
+        }
+    }
+
+    let capitals: Vec<(String, String)> = sequences
+        .iter()
+        .map(|(keys, text)| (capitalize(keys), capitalize(text)))
+        .collect();
+    sequences.extend(capitals);
+
+    let mut ret = String::new();
+    writeln!(
+        ret,
+        "# Tone-marked pinyin, generated by pinyin_zhuyin {}",
+        env!("CARGO_PKG_VERSION")
+    )
+    .unwrap();
+    writeln!(ret, "include \"%L\"").unwrap();
+    writeln!(ret).unwrap();
+
+    let mut seen = HashSet::new();
+    for (keys, text) in sequences {
+        if !seen.insert(keys.clone()) {
+            continue;
+        }
+        write!(ret, "<Multi_key>").unwrap();
+        for key in keys.chars() {
+            write!(ret, " <{}>", keysym(key)).unwrap();
+        }
+        writeln!(ret, " : \"{}\"", text).unwrap();
+    }
+    ret
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[test]
+    fn xcompose_test() {
+        let compose = xcompose(false);
+        assert!(compose.starts_with("# Tone-marked pinyin"));
+        assert!(compose.contains("\ninclude \"%L\"\n"));
+        assert!(compose.contains("\n<Multi_key> <o> <2> : \"ó\"\n"));
+        assert!(compose.contains("\n<Multi_key> <O> <2> : \"Ó\"\n"));
+        assert!(compose.contains("\n<Multi_key> <v> <3> : \"ǚ\"\n"));
+        assert!(compose.contains("\n<Multi_key> <v> <5> : \"ü\"\n"));
+        assert!(compose.contains("\n<Multi_key> <V> <5> : \"Ü\"\n"));
+        assert!(compose.contains("\n<Multi_key> <e> <asciicircum> <1> : \"ê\u{304}\"\n"));
+        assert!(compose.contains("\n<Multi_key> <e> <asciicircum> <2> : \"ế\"\n"));
+        assert!(compose.contains("\n<Multi_key> <E> <asciicircum> <4> : \"Ề\"\n"));
+        assert!(compose.contains("\n<Multi_key> <e> <asciicircum> <5> : \"ê\"\n"));
+        assert!(compose.contains("\n<Multi_key> <m> <2> : \"ḿ\"\n"));
+        assert!(compose.contains("\n<Multi_key> <m> <4> : \"m\u{300}\"\n"));
+        assert!(compose.contains("\n<Multi_key> <n> <3> : \"ň\"\n"));
+        assert!(compose.contains("\n<Multi_key> <N> <g> <4> : \"Ǹg\"\n"));
+        assert!(compose.contains("\n<Multi_key> <n> <g> <1> : \"n\u{304}g\"\n"));
+        // 6 vowels and 4 nasals in 4 tones, ü and ê alone, all in both cases
+        assert_eq!(compose.matches("<Multi_key>").count(), (10 * 4 + 2) * 2);
+
+        let compose = xcompose(true);
+        assert!(compose.contains("\n<Multi_key> <l> <v> <4> : \"lǜ\"\n"));
+        assert!(compose.contains("\n<Multi_key> <j> <u> <4> : \"jù\"\n"));
+        assert!(compose.contains("\n<Multi_key> <E> <r> <2> : \"Ér\"\n"));
+        assert!(!compose.contains("<m> <a> <5>"));
+        // a, o and e alone are both vowels and syllables, as are the nasals
+        assert_eq!(
+            compose.matches("<Multi_key>").count(),
+            (10 * 4 + 2) * 2 + (SYLLABLES.len() - 6) * 4 * 2
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Yale romanization of Mandarin.
+//!
+//! Yale writes `zh`, `z`, `c`, `q`, `x` as `j`, `dz`, `ts`, `ch`, `sy`, the
+//! apical vowel as `r` or `z` (shr, tsz), medial `i` and `u` as `y` and `w`
+//! (tyan, gwo), `ü` as `yu`, and `ao` and `ong` as `au` and `ung`. Tones take
+//! the marks of pinyin, on `r` and `z` too, and the neutral tone is unmarked.
+use std::sync::OnceLock;
+
+use crate::{
+    decode_pinyin, decode_zhuyin, encode_pinyin, lookup_syllable, mark_tone, unmark_tone, Notation,
+    Syllable, SYLLABLES,
+};
+
+/// Yale, such as `jāng`
+///
+/// Parsing a syllable without a tone mark gives the 5th tone.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Yale;
+
+impl Notation for Yale {
+    fn parse(&self, s: &str) -> Option<Syllable> {
+        Syllable::new(&decode_yale(s)?)
+    }
+
+    fn render(&self, syllable: &Syllable) -> Option<String> {
+        encode_yale(syllable.to_string())
+    }
+}
+
+/// Returns the Yale spelling of an initial
+fn initial(initial: &str) -> &'static str {
+    match initial {
+        "b" => "b",
+        "p" => "p",
+        "m" => "m",
+        "f" => "f",
+        "d" => "d",
+        "t" => "t",
+        "n" => "n",
+        "l" => "l",
+        "g" => "g",
+        "k" => "k",
+        "h" => "h",
+        "j" | "zh" => "j",
+        "q" | "ch" => "ch",
+        "x" => "sy",
+        "sh" => "sh",
+        "r" => "r",
+        "z" => "dz",
+        "c" => "ts",
+        "s" => "s",
+        _ => "",
+    }
+}
+
+/// Returns the Yale spelling of a final, which depends on the initial
+fn rhyme(initial: &str, final_: &str) -> &'static str {
+    match (initial, final_) {
+        ("", "i") => "yi",
+        ("", "io") => "yo",
+        ("", "m") => "m",
+        ("", "n") => "n",
+        ("", "ng") => "ng",
+        ("", "in") => "yin",
+        ("", "ing") => "ying",
+        ("", "u") => "wu",
+        ("", "un") => "wen",
+        ("", "ueng") => "weng",
+        ("b" | "p" | "m" | "f", "o") => "wo",
+        (_, "a") => "a",
+        (_, "o") => "o",
+        (_, "e") => "e",
+        (_, "ai") => "ai",
+        (_, "ei") => "ei",
+        (_, "ao") => "au",
+        (_, "ou") => "ou",
+        (_, "an") => "an",
+        (_, "en") => "en",
+        (_, "ang") => "ang",
+        (_, "eng") => "eng",
+        (_, "ong") => "ung",
+        (_, "er") => "er",
+        (_, "i") => "i",
+        (_, "ia") => "ya",
+        (_, "ie") => "ye",
+        (_, "iao") => "yau",
+        (_, "iu") => "you",
+        (_, "ian") => "yan",
+        (_, "in") => "in",
+        (_, "iang") => "yang",
+        (_, "ing") => "ing",
+        (_, "iong") => "yung",
+        (_, "u") => "u",
+        (_, "ua") => "wa",
+        (_, "uo") => "wo",
+        (_, "uai") => "wai",
+        (_, "ui") => "wei",
+        (_, "uan") => "wan",
+        (_, "un") => "wun",
+        (_, "uang") => "wang",
+        (_, "v") => "yu",
+        (_, "ve") => "ywe",
+        (_, "van") => "ywan",
+        (_, "vn") => "yun",
+        _ => unreachable!(),
+    }
+}
+
+/// Returns the Yale spelling of a syllable without its tone
+fn spell(consonant: &str, final_: &str) -> String {
+    match (consonant, final_) {
+        ("zh", "i") => "jr".to_owned(),
+        ("ch", "i") => "chr".to_owned(),
+        ("sh", "i") => "shr".to_owned(),
+        ("r", "i") => "r".to_owned(),
+        ("z", "i") => "dz".to_owned(),
+        ("c", "i") => "tsz".to_owned(),
+        ("s", "i") => "sz".to_owned(),
+        _ => {
+            let rhyme = rhyme(consonant, final_);
+            // `sy` before a vowel, but `s` before a `y` of the final
+            match (consonant, rhyme.starts_with('y')) {
+                ("x", true) => format!("s{}", rhyme),
+                _ => format!("{}{}", initial(consonant), rhyme),
+            }
+        }
+    }
+}
+
+/// Encode Yale
+///
+/// Returns None on a missing tone or invalid input.
+///
+/// # Examples
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(encode_yale("guo2"), Some("gwó".to_owned()));
+/// assert_eq!(encode_yale("xue2"), Some("sywé".to_owned()));
+/// assert_eq!(encode_yale("shi4"), Some("shr\u{300}".to_owned()));
+///
+/// assert_eq!(encode_yale("guo"), None);
+/// ```
+pub fn encode_yale<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (_, consonant, final_, tone) = lookup_syllable(s.as_ref())?;
+    Some(mark_tone(&spell(consonant, final_), tone))
+}
+
+/// Decode Yale
+///
+/// A syllable without a tone mark has the 5th tone. Returns None if invalid
+/// input.
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(decode_yale("Jūng"), Some("zhong1".to_owned()));
+/// assert_eq!(decode_yale("tsź"), Some("ci2".to_owned()));
+/// assert_eq!(decode_yale("syau"), Some("xiao5".to_owned()));
+/// ```
+pub fn decode_yale<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    let (s, tone) = unmark_tone(&s.as_ref().to_lowercase())?;
+    yale_table()
+        .iter()
+        .find(|(yale, _)| *yale == s)
+        .map(|(_, syllable)| format!("{}{}", syllable, tone))
+}
+
+/// Convert pinyin to Yale
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(pinyin_to_yale("tiān"), Some("tyān".to_owned()));
+/// ```
+pub fn pinyin_to_yale<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_yale(decode_pinyin(s)?)
+}
+
+/// Convert zhuyin to Yale
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(zhuyin_to_yale("ㄑㄩˋ"), Some("chyù".to_owned()));
+/// ```
+pub fn zhuyin_to_yale<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_yale(decode_zhuyin(s)?)
+}
+
+/// Convert Yale to pinyin
+///
+/// # Example
+/// ```
+/// # use pinyin_zhuyin::*;
+/// assert_eq!(yale_to_pinyin("jūng"), Some("zhōng".to_owned()));
+/// ```
+pub fn yale_to_pinyin<S>(s: S) -> Option<String>
+where
+    S: AsRef<str>,
+{
+    encode_pinyin(decode_yale(s)?)
+}
+
+/// Every syllable in Yale without its tone, paired with its numbered pinyin
+/// spelling
+fn yale_table() -> &'static [(String, &'static str)] {
+    static TABLE: OnceLock<Vec<(String, &'static str)>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        SYLLABLES
+            .entries()
+            .map(|(syllable, (consonant, final_))| (spell(consonant, final_), *syllable))
+            .collect()
+    })
+}
+
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_macros)]
+    macro_rules! s(
+        ($i:expr) => (Some($i.to_owned()));
+    );
+
+    #[test]
+    fn encode_yale_test() {
+        assert_eq!(encode_yale("zhong1"), s!("jūng"));
+        assert_eq!(encode_yale("bei3"), s!("běi"));
+        assert_eq!(encode_yale("jing1"), s!("jīng"));
+        assert_eq!(encode_yale("jia1"), s!("jyā"));
+        assert_eq!(encode_yale("qi1"), s!("chī"));
+        assert_eq!(encode_yale("qu4"), s!("chyù"));
+        assert_eq!(encode_yale("xi1"), s!("syī"));
+        assert_eq!(encode_yale("xiao3"), s!("syǎu"));
+        assert_eq!(encode_yale("xin1"), s!("syīn"));
+        assert_eq!(encode_yale("xiong2"), s!("syúng"));
+        assert_eq!(encode_yale("tian1"), s!("tyān"));
+        assert_eq!(encode_yale("liu2"), s!("lyóu"));
+        assert_eq!(encode_yale("lv4"), s!("lyù"));
+        assert_eq!(encode_yale("jue2"), s!("jywé"));
+        assert_eq!(encode_yale("gui4"), s!("gwèi"));
+        assert_eq!(encode_yale("dun1"), s!("dwūn"));
+        assert_eq!(encode_yale("huai2"), s!("hwái"));
+        assert_eq!(encode_yale("bo2"), s!("bwó"));
+        assert_eq!(encode_yale("zuo4"), s!("dzwò"));
+        assert_eq!(encode_yale("cai2"), s!("tsái"));
+        assert_eq!(encode_yale("hao3"), s!("hǎu"));
+        assert_eq!(encode_yale("wen4"), s!("wèn"));
+        assert_eq!(encode_yale("yong3"), s!("yǔng"));
+        assert_eq!(encode_yale("yuan2"), s!("ywán"));
+        // Apical vowels
+        assert_eq!(encode_yale("zhi1"), s!("jr\u{304}"));
+        assert_eq!(encode_yale("chi1"), s!("chr\u{304}"));
+        assert_eq!(encode_yale("ri4"), s!("r\u{300}"));
+        assert_eq!(encode_yale("zi3"), s!("dz\u{30c}"));
+        assert_eq!(encode_yale("si1"), s!("sz\u{304}"));
+        assert_eq!(encode_yale("de5"), s!("de"));
+
+        assert_eq!(encode_yale("bei"), None);
+        assert_eq!(encode_yale("r5"), None);
+        assert_eq!(encode_yale(""), None);
+    }
+
+    #[test]
+    fn decode_yale_test() {
+        assert_eq!(decode_yale("Gwó"), s!("guo2"));
+        assert_eq!(decode_yale("jūng"), s!("zhong1"));
+        assert_eq!(decode_yale("shŕ"), s!("shi2"));
+        assert_eq!(decode_yale("shr\u{301}"), s!("shi2"));
+        assert_eq!(decode_yale("syī"), s!("xi1"));
+        assert_eq!(decode_yale("lyu"), s!("lv5"));
+        assert_eq!(decode_yale("r"), s!("ri5"));
+
+        assert_eq!(decode_yale("běijīng"), None);
+        assert_eq!(decode_yale("zhong"), None);
+        assert_eq!(decode_yale("gwóó"), None);
+        assert_eq!(decode_yale("gwǒó"), None);
+        assert_eq!(decode_yale(""), None);
+
+        // Every spelling belongs to one syllable
+        for syllable in SYLLABLES.keys() {
+            for tone in 1..=5 {
+                let syllable = format!("{}{}", syllable, tone);
+                let yale = encode_yale(&syllable).unwrap();
+                assert_eq!(decode_yale(&yale), Some(syllable));
+            }
+        }
+    }
+
+    #[test]
+    fn yale_conversion_test() {
+        assert_eq!(pinyin_to_yale("zhōu"), s!("jōu"));
+        assert_eq!(zhuyin_to_yale("ㄒㄩㄥˊ"), s!("syúng"));
+        assert_eq!(yale_to_pinyin("Gwó"), s!("guó"));
+
+        assert_eq!(pinyin_to_yale("啊"), None);
+        assert_eq!(zhuyin_to_yale("ㄩㄝㄝ"), None);
+        assert_eq!(yale_to_pinyin("mao"), None);
+    }
+}